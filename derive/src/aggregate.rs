@@ -0,0 +1,179 @@
+use proc_macro2::Span;
+use quote::quote;
+use syn::{DeriveInput, Error, Path, Type};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Fold,
+    TryFold,
+    ForEach,
+    Find,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Fold => "fold",
+            Kind::TryFold => "try_fold",
+            Kind::ForEach => "for_each",
+            Kind::Find => "find",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Args {
+    kind: Option<Kind>,
+    item: Option<Type>,
+    acc: Option<Type>,
+    brk: Option<Type>,
+    init: Option<Path>,
+    f: Option<Path>,
+    options: Option<Path>,
+}
+
+impl Args {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut args = Args::default();
+        let mut found = false;
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("aggregate"))
+        {
+            found = true;
+            attr.parse_nested_meta(|meta| {
+                let kind = [Kind::Fold, Kind::TryFold, Kind::ForEach, Kind::Find]
+                    .into_iter()
+                    .find(|k| meta.path.is_ident(k.name()));
+                if let Some(kind) = kind {
+                    if args.kind.replace(kind).is_some() {
+                        return Err(meta.error("aggregator kind given twice"));
+                    }
+                } else if meta.path.is_ident("item") {
+                    args.item = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("acc") {
+                    args.acc = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("break") {
+                    args.brk = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("init") {
+                    args.init = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("f") {
+                    args.f = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("options") {
+                    args.options = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unknown aggregate argument"));
+                }
+                Ok(())
+            })?;
+        }
+        if !found {
+            return Err(Error::new(
+                Span::call_site(),
+                "missing `#[aggregate(...)]` attribute",
+            ));
+        }
+        Ok(args)
+    }
+}
+
+/// Check that `value` is present if and only if `kind` uses it.
+fn take<T>(kind: Kind, used: bool, key: &str, value: Option<T>) -> syn::Result<Option<T>> {
+    match (used, value) {
+        (true, None) => Err(Error::new(
+            Span::call_site(),
+            format!("`{key}` is required by `{}` aggregators", kind.name()),
+        )),
+        (false, Some(_)) => Err(Error::new(
+            Span::call_site(),
+            format!("`{key}` is not used by `{}` aggregators", kind.name()),
+        )),
+        (_, value) => Ok(value),
+    }
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let args = Args::parse(&input)?;
+    let Some(kind) = args.kind else {
+        return Err(Error::new(
+            Span::call_site(),
+            "missing aggregator kind: one of `fold`, `try_fold`, `for_each` or `find`",
+        ));
+    };
+    let has_acc = matches!(kind, Kind::Fold | Kind::TryFold);
+    let item = take(kind, true, "item", args.item)?;
+    let acc = take(kind, has_acc, "acc", args.acc)?;
+    let brk = take(kind, kind == Kind::TryFold, "break", args.brk)?;
+    let init = take(kind, has_acc, "init", args.init)?;
+    let f = take(kind, true, "f", args.f)?;
+    let options = args.options.map(|options| {
+        quote! {
+            const OPTIONS: ::serde_deser_iter::Options = #options;
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = match kind {
+        Kind::Fold => quote! {
+            impl #impl_generics ::serde_deser_iter::deep::FoldAggregator for #name #ty_generics #where_clause {
+                type Item = #item;
+                type Acc = #acc;
+
+                #options
+
+                fn init() -> Self::Acc {
+                    #init()
+                }
+
+                fn f(acc: Self::Acc, item: Self::Item) -> Self::Acc {
+                    #f(acc, item)
+                }
+            }
+        },
+        Kind::TryFold => quote! {
+            impl #impl_generics ::serde_deser_iter::deep::TryFoldAggregator for #name #ty_generics #where_clause {
+                type Item = #item;
+                type Acc = #acc;
+                type Break = #brk;
+
+                #options
+
+                fn init() -> Self::Acc {
+                    #init()
+                }
+
+                fn f(
+                    acc: Self::Acc,
+                    item: Self::Item,
+                ) -> ::core::ops::ControlFlow<Self::Break, Self::Acc> {
+                    #f(acc, item)
+                }
+            }
+        },
+        Kind::ForEach => quote! {
+            impl #impl_generics ::serde_deser_iter::deep::ForEachAggregator for #name #ty_generics #where_clause {
+                type Item = #item;
+
+                #options
+
+                fn f(item: Self::Item) {
+                    #f(item)
+                }
+            }
+        },
+        Kind::Find => quote! {
+            impl #impl_generics ::serde_deser_iter::deep::FindAggregator for #name #ty_generics #where_clause {
+                type Item = #item;
+
+                #options
+
+                fn f(item: &Self::Item) -> bool {
+                    #f(item)
+                }
+            }
+        },
+    };
+    Ok(body)
+}
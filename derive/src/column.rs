@@ -0,0 +1,307 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Error, Field, Fields, GenericArgument, Ident, LitStr, PathArguments, Type,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Count,
+    Sum,
+    Min,
+    Max,
+    DistinctCount,
+}
+
+impl Kind {
+    const ALL: [Kind; 5] = [
+        Kind::Count,
+        Kind::Sum,
+        Kind::Min,
+        Kind::Max,
+        Kind::DistinctCount,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Count => "count",
+            Kind::Sum => "sum",
+            Kind::Min => "min",
+            Kind::Max => "max",
+            Kind::DistinctCount => "distinct_count",
+        }
+    }
+}
+
+/// A field of the stats struct, with its parsed `column` attribute.
+struct Column<'a> {
+    field: &'a Field,
+    ident: &'a Ident,
+    kind: Kind,
+    /// Index of the source in the row struct, if any.
+    source: Option<usize>,
+}
+
+/// A field of the items, deserialized in the row struct.
+struct Source {
+    name: LitStr,
+    ty: Type,
+}
+
+/// Inner type of `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+fn parse_column<'a>(field: &'a Field, sources: &mut Vec<Source>) -> syn::Result<Column<'a>> {
+    let ident = field
+        .ident
+        .as_ref()
+        .ok_or_else(|| Error::new_spanned(field, "columns must be named fields"))?;
+    let mut kind = None;
+    let mut source = None;
+    let mut ty = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("column")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(k) = Kind::ALL.into_iter().find(|k| meta.path.is_ident(k.name())) {
+                if kind.replace(k).is_some() {
+                    return Err(meta.error("column kind given twice"));
+                }
+            } else if meta.path.is_ident("source") {
+                source = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("ty") {
+                ty = Some(meta.value()?.parse::<Type>()?);
+            } else {
+                return Err(meta.error("unknown column argument"));
+            }
+            Ok(())
+        })?;
+    }
+    let kind = kind.ok_or_else(|| {
+        Error::new_spanned(
+            field,
+            "missing column kind: one of `count`, `sum`, `min`, `max` or `distinct_count`",
+        )
+    })?;
+    let source_ty = match (kind, ty) {
+        (Kind::Count, None) => None,
+        (Kind::Count, Some(_)) => {
+            return Err(Error::new_spanned(field, "`count` columns have no source"))
+        }
+        (_, Some(ty)) => Some(ty),
+        (Kind::Sum, None) => Some(field.ty.clone()),
+        (Kind::Min | Kind::Max, None) => Some(
+            option_inner(&field.ty)
+                .ok_or_else(|| {
+                    Error::new_spanned(
+                        &field.ty,
+                        "`min` and `max` columns must be of type `Option<T>`, or give the source type with `ty = T`",
+                    )
+                })?
+                .clone(),
+        ),
+        (Kind::DistinctCount, None) => {
+            return Err(Error::new_spanned(
+                field,
+                "`distinct_count` columns need the source type, given with `ty = T`",
+            ))
+        }
+    };
+    let source = match (source_ty, source) {
+        (None, None) => None,
+        (None, Some(source)) => {
+            return Err(Error::new_spanned(source, "`count` columns have no source"))
+        }
+        (Some(_), None) => {
+            return Err(Error::new_spanned(
+                field,
+                "missing source field, given with `source = \"name\"`",
+            ))
+        }
+        (Some(ty), Some(name)) => {
+            match sources.iter().position(|s| s.name.value() == name.value()) {
+                Some(idx) => Some(idx),
+                None => {
+                    sources.push(Source { name, ty });
+                    Some(sources.len() - 1)
+                }
+            }
+        }
+    };
+    Ok(Column {
+        field,
+        ident,
+        kind,
+        source,
+    })
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if input.generics.type_params().next().is_some()
+        || input.generics.const_params().next().is_some()
+    {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`ColumnAggregate` only supports lifetime parameters",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            Span::call_site(),
+            "`ColumnAggregate` can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(
+            Span::call_site(),
+            "`ColumnAggregate` can only be derived for structs with named fields",
+        ));
+    };
+    let mut sources = Vec::new();
+    let columns = fields
+        .named
+        .iter()
+        .map(|field| parse_column(field, &mut sources))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let lifetimes: Vec<_> = generics.lifetimes().map(|l| &l.lifetime).collect();
+    let source_fields = sources.iter().enumerate().map(|(idx, source)| {
+        let ident = format_ident!("__source{}", idx);
+        let name = &source.name;
+        let ty = &source.ty;
+        quote! {
+            #[serde(rename = #name)]
+            #ident: #ty
+        }
+    });
+    let state_fields = columns.iter().map(|column| {
+        let ident = column.ident;
+        let ty = match column.kind {
+            Kind::DistinctCount => {
+                let source_ty = &sources[column.source.unwrap()].ty;
+                quote!(::serde_deser_iter::__private::BTreeSet<#source_ty>)
+            }
+            _ => {
+                let ty = &column.field.ty;
+                quote!(#ty)
+            }
+        };
+        quote!(#ident: #ty)
+    });
+    let init_fields = columns.iter().map(|column| {
+        let ident = column.ident;
+        quote!(#ident: ::core::default::Default::default())
+    });
+    let updates = columns.iter().enumerate().map(|(pos, column)| {
+        let ident = column.ident;
+        let value = column.source.map(|idx| {
+            let source = format_ident!("__source{}", idx);
+            // The source is moved into the last column using it, and cloned for the others.
+            if columns[pos + 1..].iter().any(|c| c.source == Some(idx)) {
+                quote!(::core::clone::Clone::clone(&row.#source))
+            } else {
+                quote!(row.#source)
+            }
+        });
+        match column.kind {
+            Kind::Count => quote!(acc.#ident += 1;),
+            Kind::Sum => quote!(acc.#ident += #value;),
+            Kind::Min => quote! {
+                let value = #value;
+                match &acc.#ident {
+                    ::core::option::Option::Some(min) if *min <= value => {}
+                    _ => acc.#ident = ::core::option::Option::Some(value),
+                }
+            },
+            Kind::Max => quote! {
+                let value = #value;
+                match &acc.#ident {
+                    ::core::option::Option::Some(max) if *max >= value => {}
+                    _ => acc.#ident = ::core::option::Option::Some(value),
+                }
+            },
+            Kind::DistinctCount => quote!(acc.#ident.insert(#value);),
+        }
+    });
+    let finalize_fields = columns.iter().map(|column| {
+        let ident = column.ident;
+        match column.kind {
+            Kind::DistinctCount => quote!(#ident: acc.#ident.len()),
+            _ => quote!(#ident: acc.#ident),
+        }
+    });
+
+    Ok(quote! {
+        const _: () = {
+            #[derive(::serde_deser_iter::__private::serde::Deserialize)]
+            #[serde(crate = "::serde_deser_iter::__private::serde")]
+            pub struct __ColumnRow #generics #where_clause {
+                #(#source_fields,)*
+                #[serde(skip)]
+                __marker: ::core::marker::PhantomData<(#(&#lifetimes (),)*)>,
+            }
+
+            pub struct __ColumnState #generics #where_clause {
+                #(#state_fields,)*
+            }
+
+            impl #impl_generics ::serde_deser_iter::deep::Aggregator for #name #ty_generics #where_clause {
+                type Acc = __ColumnState #ty_generics;
+                type Item = __ColumnRow #ty_generics;
+                type Break = ::core::convert::Infallible;
+                type Value = Self;
+
+                fn init() -> Self::Acc {
+                    __ColumnState {
+                        #(#init_fields,)*
+                    }
+                }
+
+                #[allow(unused_variables)]
+                fn try_fold(
+                    mut acc: Self::Acc,
+                    row: Self::Item,
+                ) -> ::core::ops::ControlFlow<Self::Break, Self::Acc> {
+                    #(#updates)*
+                    ::core::ops::ControlFlow::Continue(acc)
+                }
+
+                fn finalize(x: ::core::ops::ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+                    match x {
+                        ::core::ops::ControlFlow::Continue(acc) => #name {
+                            #(#finalize_fields,)*
+                        },
+                        ::core::ops::ControlFlow::Break(infallible) => match infallible {},
+                    }
+                }
+            }
+
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Aggregate the columns over the items of the sequence read by `deserializer`.
+                pub fn from_top_level<'__de, D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: ::serde_deser_iter::top_level::DeserializerExt<'__de, __ColumnRow #ty_generics>,
+                    #('__de: #lifetimes,)*
+                {
+                    deserializer.aggregate::<Self>()
+                }
+            }
+        };
+    })
+}
@@ -0,0 +1,142 @@
+//! Derive macros for [serde_deser_iter](https://docs.rs/serde_deser_iter).
+//!
+//! This crate is not meant to be used directly, but through the `derive` feature of
+//! `serde_deser_iter`, which re-exports its macros.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, Error};
+
+mod aggregate;
+mod column;
+
+/// Implement one of the `deep` aggregator traits from plain functions.
+///
+/// The kind of aggregator and its parts are given through the `aggregate` attribute:
+///
+/// | kind       | implemented trait   | required keys                     |
+/// |------------|---------------------|-----------------------------------|
+/// | `fold`     | `FoldAggregator`    | `item`, `acc`, `init`, `f`        |
+/// | `try_fold` | `TryFoldAggregator` | `item`, `acc`, `break`, `init`, `f` |
+/// | `for_each` | `ForEachAggregator` | `item`, `f`                       |
+/// | `find`     | `FindAggregator`    | `item`, `f`                       |
+///
+/// `item`, `acc` and `break` are types, `init` and `f` are paths to functions with the
+/// signatures of the corresponding trait functions. The optional `options` key is a path to
+/// a constant of type `Options`, used as the `OPTIONS` of the aggregator.
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use serde_deser_iter::deep::{Aggregate, Fold, StreamSeqDeser};
+///
+/// #[derive(serde::Deserialize)]
+/// struct DataEntry {
+///     subscribed_to: Vec<String>,
+/// }
+///
+/// fn collect_channels(mut acc: HashSet<String>, entry: DataEntry) -> HashSet<String> {
+///     acc.extend(entry.subscribed_to);
+///     acc
+/// }
+///
+/// #[derive(Aggregate)]
+/// #[aggregate(fold, item = DataEntry, acc = HashSet<String>, init = HashSet::new, f = collect_channels)]
+/// struct Imp;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Data {
+///     result: StreamSeqDeser<Fold<Imp>>,
+/// }
+///
+/// let json = r#"{"result": [{"subscribed_to": ["rust"]}, {"subscribed_to": ["rust", "sticks"]}]}"#;
+/// let data: Data = serde_json::from_str(json).unwrap();
+/// assert_eq!(data.result.len(), 2);
+/// ```
+///
+/// ```
+/// use serde_deser_iter::{
+///     deep::{Aggregate, Find, StreamSeqDeser},
+///     Drain, Options,
+/// };
+///
+/// const VALIDATE: Options = Options::new().drain(Drain::Validate);
+///
+/// fn is_one(item: &u32) -> bool {
+///     *item == 1
+/// }
+///
+/// #[derive(Aggregate)]
+/// #[aggregate(find, item = u32, f = is_one, options = VALIDATE)]
+/// struct FindOne;
+///
+/// let error = serde_json::from_str::<StreamSeqDeser<Find<FindOne>>>(r#"[1, "two", 3]"#)
+///     .err()
+///     .unwrap();
+/// assert!(error.to_string().starts_with("invalid type"));
+/// ```
+#[proc_macro_derive(Aggregate, attributes(aggregate))]
+pub fn derive_aggregate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    aggregate::expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Aggregate several columns of a sequence of objects in a single pass.
+///
+/// Every field of the struct is a column, annotated with its kind and, except for `count`,
+/// with the name of the field of the items it is computed from:
+///
+/// | kind             | column type         | value                                  |
+/// |------------------|---------------------|----------------------------------------|
+/// | `count`          | any integer         | the number of items                    |
+/// | `sum`            | `T: AddAssign`      | the sum of the source field            |
+/// | `min`            | `Option<T>`         | the minimum of the source field        |
+/// | `max`            | `Option<T>`         | the maximum of the source field        |
+/// | `distinct_count` | `usize`             | the number of distinct source values   |
+///
+/// The type of the source field is deduced from the column type, except for `distinct_count`
+/// where it must be given with `ty = T` (and be `Ord`, the distinct values being kept in
+/// a `BTreeSet`). Only the referenced fields of the items are deserialized, and several
+/// columns can share the same source field, which is then cloned for all of them but the
+/// last. The struct may have lifetime parameters, for instance to count distinct `&'a str`
+/// values borrowed from the input.
+///
+/// The macro implements `deep::Aggregator` for the struct, so it can be used with
+/// `StreamSeqDeser` inside a larger document, and adds a `from_top_level` function
+/// aggregating a top-level sequence.
+///
+/// ```
+/// use serde_deser_iter::deep::ColumnAggregate;
+///
+/// #[derive(ColumnAggregate, Debug, PartialEq)]
+/// struct Stats {
+///     #[column(count)]
+///     orders: usize,
+///     #[column(sum, source = "price")]
+///     revenue: f64,
+///     #[column(max, source = "timestamp")]
+///     last_order: Option<u64>,
+///     #[column(distinct_count, source = "user", ty = String)]
+///     customers: usize,
+/// }
+///
+/// let json = r#"[
+///     {"user": "ann", "price": 12.5, "timestamp": 3, "comment": "quick"},
+///     {"user": "bob", "price": 5.0, "timestamp": 7},
+///     {"user": "ann", "price": 2.5, "timestamp": 5}
+/// ]"#;
+/// let mut deserializer = serde_json::Deserializer::from_str(json);
+/// let stats = Stats::from_top_level(&mut deserializer).unwrap();
+/// assert_eq!(
+///     stats,
+///     Stats { orders: 3, revenue: 20.0, last_order: Some(7), customers: 2 }
+/// );
+/// ```
+#[proc_macro_derive(ColumnAggregate, attributes(column))]
+pub fn derive_column_aggregate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    column::expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
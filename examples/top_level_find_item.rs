@@ -19,6 +19,7 @@ fn main() -> anyhow::Result<()> {
     let buffered_file: BufReader<File> = BufReader::new(File::open(example_json_path)?);
     let mut json_deserializer = serde_json::Deserializer::from_reader(buffered_file);
 
+    #[allow(clippy::needless_late_init)]
     let search_result: Result<Option<DataEntry>, serde_json::Error>;
     search_result =
         json_deserializer.find(|entry: &DataEntry| !entry.subscribed_to.contains(&"rust".into()));
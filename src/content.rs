@@ -0,0 +1,605 @@
+//! A format-agnostic buffered form of a value.
+//!
+//! Items are first captured as [`Buffered`] content, which only fails on malformed input, and
+//! then converted to their type through [`ContentDeserializer`], so that a conversion failure
+//! leaves the format deserializer ready to read the next item.
+
+use alloc::{borrow::ToOwned, boxed::Box, string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer},
+        Deserialize, DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, MapAccess,
+        SeqAccess, Unexpected, VariantAccess, Visitor,
+    },
+    forward_to_deserialize_any,
+};
+
+/// Upper bound of the capacity preallocated from size hints.
+const MAX_PREALLOCATED: usize = 4096;
+
+pub(crate) enum Content<'de> {
+    Bool(bool),
+    U64(u64),
+    I64(i64),
+    U128(u128),
+    I128(i128),
+    F64(f64),
+    Char(char),
+    String(String),
+    Str(&'de str),
+    ByteBuf(Vec<u8>),
+    Bytes(&'de [u8]),
+    None,
+    Some(Box<Content<'de>>),
+    Unit,
+    Newtype(Box<Content<'de>>),
+    Seq(Vec<Content<'de>>),
+    Map(Vec<(Content<'de>, Content<'de>)>),
+}
+
+impl<'de> Content<'de> {
+    fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Content::Bool(b) => Unexpected::Bool(*b),
+            Content::U64(n) => Unexpected::Unsigned(*n),
+            Content::I64(n) => Unexpected::Signed(*n),
+            Content::U128(_) | Content::I128(_) => Unexpected::Other("128-bit integer"),
+            Content::F64(f) => Unexpected::Float(*f),
+            Content::Char(c) => Unexpected::Char(*c),
+            Content::String(s) => Unexpected::Str(s),
+            Content::Str(s) => Unexpected::Str(s),
+            Content::ByteBuf(b) => Unexpected::Bytes(b),
+            Content::Bytes(b) => Unexpected::Bytes(b),
+            Content::None | Content::Some(_) => Unexpected::Option,
+            Content::Unit => Unexpected::Unit,
+            Content::Newtype(_) => Unexpected::NewtypeStruct,
+            Content::Seq(_) => Unexpected::Seq,
+            Content::Map(_) => Unexpected::Map,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Content<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ContentVisitor)
+    }
+}
+
+/// A [`Content`] captured from a format, along with whether the format is human-readable,
+/// which its replay reports as well.
+pub(crate) struct Buffered<'de> {
+    content: Content<'de>,
+    human_readable: bool,
+}
+
+impl<'de> Buffered<'de> {
+    /// Replay the content as a value.
+    pub(crate) fn into_deserializer<E>(self) -> ContentDeserializer<'de, E> {
+        ContentDeserializer::new(self.content, self.human_readable)
+    }
+
+    /// Replay the content as a map key.
+    pub(crate) fn into_key_deserializer<E>(self) -> KeyDeserializer<'de, E> {
+        KeyDeserializer::new(self.content, self.human_readable)
+    }
+}
+
+impl<'de> Deserialize<'de> for Buffered<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let human_readable = deserializer.is_human_readable();
+        Content::deserialize(deserializer).map(|content| Buffered {
+            content,
+            human_readable,
+        })
+    }
+}
+
+struct ContentVisitor;
+
+impl<'de> Visitor<'de> for ContentVisitor {
+    type Value = Content<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Content::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Content::I64(v))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(Content::I128(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Content::U64(v))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(Content::U128(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Content::F64(v))
+    }
+
+    fn visit_char<E: de::Error>(self, v: char) -> Result<Self::Value, E> {
+        Ok(Content::Char(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Content::String(v.to_owned()))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Content::Str(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Content::String(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Content::ByteBuf(v.to_owned()))
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Content::Bytes(v))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Content::ByteBuf(v))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Content::None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Content::deserialize(deserializer).map(|v| Content::Some(Box::new(v)))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Content::Unit)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Content::deserialize(deserializer).map(|v| Content::Newtype(Box::new(v)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut vec = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED));
+        while let Some(e) = seq.next_element()? {
+            vec.push(e);
+        }
+        Ok(Content::Seq(vec))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut vec = Vec::with_capacity(map.size_hint().unwrap_or(0).min(MAX_PREALLOCATED));
+        while let Some(entry) = map.next_entry()? {
+            vec.push(entry);
+        }
+        Ok(Content::Map(vec))
+    }
+
+    fn visit_enum<A>(self, _data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        Err(de::Error::custom(
+            "enums given as such by the format cannot be buffered",
+        ))
+    }
+}
+
+/// Deserializer replaying a [`Content`], failing with the error type `E` of the format.
+pub(crate) struct ContentDeserializer<'de, E> {
+    content: Content<'de>,
+    human_readable: bool,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> ContentDeserializer<'de, E> {
+    fn new(content: Content<'de>, human_readable: bool) -> Self {
+        Self {
+            content,
+            human_readable,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, E> IntoDeserializer<'de, E> for ContentDeserializer<'de, E>
+where
+    E: de::Error,
+{
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn visit_seq<'de, V, E>(
+    content: Vec<Content<'de>>,
+    human_readable: bool,
+    visitor: V,
+) -> Result<V::Value, E>
+where
+    V: Visitor<'de>,
+    E: de::Error,
+{
+    let mut seq = SeqDeserializer::new(
+        content
+            .into_iter()
+            .map(|v| ContentDeserializer::new(v, human_readable)),
+    );
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+fn visit_map<'de, V, E>(
+    content: Vec<(Content<'de>, Content<'de>)>,
+    human_readable: bool,
+    visitor: V,
+) -> Result<V::Value, E>
+where
+    V: Visitor<'de>,
+    E: de::Error,
+{
+    let mut map = MapDeserializer::new(content.into_iter().map(|(k, v)| {
+        (
+            KeyDeserializer::new(k, human_readable),
+            ContentDeserializer::new(v, human_readable),
+        )
+    }));
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+impl<'de, E> Deserializer<'de> for ContentDeserializer<'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        let human_readable = self.human_readable;
+        match self.content {
+            Content::Bool(v) => visitor.visit_bool(v),
+            Content::U64(v) => visitor.visit_u64(v),
+            Content::I64(v) => visitor.visit_i64(v),
+            Content::U128(v) => visitor.visit_u128(v),
+            Content::I128(v) => visitor.visit_i128(v),
+            Content::F64(v) => visitor.visit_f64(v),
+            Content::Char(v) => visitor.visit_char(v),
+            Content::String(v) => visitor.visit_string(v),
+            Content::Str(v) => visitor.visit_borrowed_str(v),
+            Content::ByteBuf(v) => visitor.visit_byte_buf(v),
+            Content::Bytes(v) => visitor.visit_borrowed_bytes(v),
+            Content::None => visitor.visit_none(),
+            Content::Some(v) => visitor.visit_some(ContentDeserializer::new(*v, human_readable)),
+            Content::Unit => visitor.visit_unit(),
+            Content::Newtype(v) => {
+                visitor.visit_newtype_struct(ContentDeserializer::new(*v, human_readable))
+            }
+            Content::Seq(v) => visit_seq(v, human_readable, visitor),
+            Content::Map(v) => visit_map(v, human_readable, visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.content {
+            Content::None | Content::Unit => visitor.visit_none(),
+            Content::Some(v) => {
+                visitor.visit_some(ContentDeserializer::new(*v, self.human_readable))
+            }
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.content {
+            Content::Newtype(v) => {
+                visitor.visit_newtype_struct(ContentDeserializer::new(*v, self.human_readable))
+            }
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        let (variant, value) = match self.content {
+            Content::Map(mut entries) if entries.len() == 1 => {
+                let (variant, value) = entries.pop().unwrap();
+                (variant, Some(value))
+            }
+            content @ (Content::String(_) | Content::Str(_)) => (content, None),
+            other => {
+                return Err(de::Error::invalid_type(
+                    other.unexpected(),
+                    &"a string or a map with a single key",
+                ))
+            }
+        };
+        visitor.visit_enum(EnumDeserializer {
+            variant,
+            value,
+            human_readable: self.human_readable,
+            marker: PhantomData,
+        })
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+}
+
+/// Deserializer replaying a map key.
+///
+/// Human-readable formats such as JSON write all map keys as strings and parse them back
+/// to the requested type, which is done here as well for numbers and booleans. The keys of
+/// other formats keep their type, and are replayed as any other value.
+pub(crate) struct KeyDeserializer<'de, E> {
+    content: Content<'de>,
+    human_readable: bool,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> KeyDeserializer<'de, E> {
+    fn new(content: Content<'de>, human_readable: bool) -> Self {
+        Self {
+            content,
+            human_readable,
+            marker: PhantomData,
+        }
+    }
+
+    fn into_content(self) -> ContentDeserializer<'de, E> {
+        ContentDeserializer::new(self.content, self.human_readable)
+    }
+}
+
+impl<'de, E> IntoDeserializer<'de, E> for KeyDeserializer<'de, E>
+where
+    E: de::Error,
+{
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! parse_key {
+    ($($method:ident => $visit:ident($ty:ty);)*) => {$(
+        fn $method<V>(self, visitor: V) -> Result<V::Value, E>
+        where
+            V: Visitor<'de>,
+        {
+            let parsed = match &self.content {
+                Content::String(s) if self.human_readable => s.parse::<$ty>().ok(),
+                Content::Str(s) if self.human_readable => s.parse::<$ty>().ok(),
+                _ => None,
+            };
+            match parsed {
+                Some(v) => visitor.$visit(v),
+                None => self.into_content().$method(visitor),
+            }
+        }
+    )*};
+}
+
+macro_rules! forward_key {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, E>
+        where
+            V: Visitor<'de>,
+        {
+            self.into_content().$method($($arg,)* visitor)
+        }
+    )*};
+}
+
+impl<'de, E> Deserializer<'de> for KeyDeserializer<'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    parse_key! {
+        deserialize_bool => visit_bool(bool);
+        deserialize_i8 => visit_i8(i8);
+        deserialize_i16 => visit_i16(i16);
+        deserialize_i32 => visit_i32(i32);
+        deserialize_i64 => visit_i64(i64);
+        deserialize_i128 => visit_i128(i128);
+        deserialize_u8 => visit_u8(u8);
+        deserialize_u16 => visit_u16(u16);
+        deserialize_u32 => visit_u32(u32);
+        deserialize_u64 => visit_u64(u64);
+        deserialize_u128 => visit_u128(u128);
+        deserialize_f32 => visit_f32(f32);
+        deserialize_f64 => visit_f64(f64);
+    }
+
+    forward_key! {
+        deserialize_any();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+}
+
+struct EnumDeserializer<'de, E> {
+    variant: Content<'de>,
+    value: Option<Content<'de>>,
+    human_readable: bool,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> EnumAccess<'de> for EnumDeserializer<'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+    type Variant = VariantDeserializer<'de, E>;
+
+    fn variant_seed<S>(self, seed: S) -> Result<(S::Value, Self::Variant), E>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let variant =
+            seed.deserialize(ContentDeserializer::new(self.variant, self.human_readable))?;
+        Ok((
+            variant,
+            VariantDeserializer {
+                value: self.value,
+                human_readable: self.human_readable,
+                marker: PhantomData,
+            },
+        ))
+    }
+}
+
+struct VariantDeserializer<'de, E> {
+    value: Option<Content<'de>>,
+    human_readable: bool,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> VariantAccess<'de> for VariantDeserializer<'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), E> {
+        match self.value {
+            None | Some(Content::Unit) => Ok(()),
+            Some(other) => Err(de::Error::invalid_type(other.unexpected(), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<S>(self, seed: S) -> Result<S::Value, E>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(ContentDeserializer::new(value, self.human_readable)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Content::Seq(v)) => visit_seq(v, self.human_readable, visitor),
+            Some(other) => Err(de::Error::invalid_type(
+                other.unexpected(),
+                &"tuple variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Content::Map(v)) => visit_map(v, self.human_readable, visitor),
+            Some(Content::Seq(v)) => visit_seq(v, self.human_readable, visitor),
+            Some(other) => Err(de::Error::invalid_type(
+                other.unexpected(),
+                &"struct variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
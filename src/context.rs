@@ -0,0 +1,496 @@
+//! Error context: index of the failing item, and path of the failing value inside of it.
+//!
+//! The item is deserialized through [`Wrap`]pers of the format deserializer, its visitors and
+//! accessors, which keep track of the current position as a chain of [`Segment`]s living on
+//! the stack. The first seed of the chain an error goes through prefixes it with the path, so
+//! that the error is only rewritten once.
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use core::{cell::Cell, fmt, str};
+
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+
+use crate::Options;
+
+/// Deserialize the item at `index` with `seed`, attaching its position to errors if enabled
+/// by `options`.
+pub(crate) fn deserialize<'de, S, D>(
+    seed: S,
+    deserializer: D,
+    options: Options,
+    index: usize,
+) -> Result<S::Value, D::Error>
+where
+    S: DeserializeSeed<'de>,
+    D: Deserializer<'de>,
+{
+    if !options.context {
+        return seed.deserialize(deserializer);
+    }
+    let root = Segment::Root(Cell::new(false));
+    Element {
+        seed,
+        segment: Segment::Index(&root, index),
+    }
+    .deserialize(deserializer)
+}
+
+/// Maximum length of the map keys kept when they cannot be borrowed from the input.
+const INLINE_KEY_LEN: usize = 32;
+
+/// A map key, as far as it could be captured.
+#[derive(Clone, Copy)]
+enum Key<'de> {
+    Unknown,
+    Borrowed(&'de str),
+    Inline {
+        buf: [u8; INLINE_KEY_LEN],
+        len: usize,
+        truncated: bool,
+    },
+    Signed(i64),
+    Unsigned(u64),
+}
+
+impl<'de> Key<'de> {
+    fn inline(key: &str) -> Self {
+        let mut len = key.len().min(INLINE_KEY_LEN);
+        while !key.is_char_boundary(len) {
+            len -= 1;
+        }
+        let mut buf = [0; INLINE_KEY_LEN];
+        buf[..len].copy_from_slice(&key.as_bytes()[..len]);
+        Key::Inline {
+            buf,
+            len,
+            truncated: len < key.len(),
+        }
+    }
+}
+
+impl<'de> fmt::Display for Key<'de> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Unknown => f.write_str("?"),
+            Key::Borrowed(key) => f.write_str(key),
+            Key::Inline {
+                buf,
+                len,
+                truncated,
+            } => {
+                f.write_str(str::from_utf8(&buf[..*len]).map_err(|_| fmt::Error)?)?;
+                if *truncated {
+                    f.write_str("...")?;
+                }
+                Ok(())
+            }
+            Key::Signed(key) => write!(f, "{key}"),
+            Key::Unsigned(key) => write!(f, "{key}"),
+        }
+    }
+}
+
+/// A step of the path from the item to the current value.
+enum Segment<'p, 'de> {
+    /// The sequence being aggregated, with whether an error was already reported.
+    Root(Cell<bool>),
+    Index(&'p Segment<'p, 'de>, usize),
+    Key(&'p Segment<'p, 'de>, Key<'de>),
+}
+
+impl<'p, 'de> Segment<'p, 'de> {
+    fn reported(&self) -> &Cell<bool> {
+        match self {
+            Segment::Root(reported) => reported,
+            Segment::Index(parent, _) | Segment::Key(parent, _) => parent.reported(),
+        }
+    }
+
+    fn report<E: de::Error>(&self, error: E) -> E {
+        if self.reported().replace(true) {
+            error
+        } else {
+            E::custom(format_args!("{self}: {error}"))
+        }
+    }
+}
+
+impl<'p, 'de> fmt::Display for Segment<'p, 'de> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Root(_) => Ok(()),
+            Segment::Index(parent, index) => write!(f, "{parent}[{index}]"),
+            Segment::Key(parent, key) => write!(f, "{parent}.{key}"),
+        }
+    }
+}
+
+/// Seed of a value at a new position, reporting the errors it fails with.
+struct Element<'p, 'de, S> {
+    seed: S,
+    segment: Segment<'p, 'de>,
+}
+
+impl<'p, 'de, S> DeserializeSeed<'de> for Element<'p, 'de, S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let segment = self.segment;
+        self.seed
+            .deserialize(Wrap::new(deserializer, &segment, None))
+            .map_err(|e| segment.report(e))
+    }
+}
+
+/// Wrapper of a deserializer, visitor, seed or enum accessor at the current position.
+///
+/// When deserializing a map key, `key` is the slot where it is captured.
+struct Wrap<'p, 'de, X> {
+    inner: X,
+    segment: &'p Segment<'p, 'de>,
+    key: Option<&'p Cell<Key<'de>>>,
+}
+
+impl<'p, 'de, X> Wrap<'p, 'de, X> {
+    fn new(inner: X, segment: &'p Segment<'p, 'de>, key: Option<&'p Cell<Key<'de>>>) -> Self {
+        Self {
+            inner,
+            segment,
+            key,
+        }
+    }
+
+    fn with<Y>(&self, inner: Y) -> Wrap<'p, 'de, Y> {
+        Wrap::new(inner, self.segment, self.key)
+    }
+}
+
+impl<'p, 'de, S> DeserializeSeed<'de> for Wrap<'p, 'de, S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserializer = self.with(deserializer);
+        self.inner.deserialize(deserializer)
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            let visitor = self.with(visitor);
+            self.inner.$method($($arg,)* visitor)
+        }
+    )*};
+}
+
+impl<'p, 'de, D> Deserializer<'de> for Wrap<'p, 'de, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty) $(=> $capture:expr)?;)*) => {$(
+        fn $method<E>(self, v: $ty) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            $(
+                if let Some(key) = self.key {
+                    key.set($capture(v));
+                }
+            )?
+            self.inner.$method(v)
+        }
+    )*};
+}
+
+impl<'p, 'de, V> Visitor<'de> for Wrap<'p, 'de, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i8(i8) => |v| Key::Signed(i64::from(v));
+        visit_i16(i16) => |v| Key::Signed(i64::from(v));
+        visit_i32(i32) => |v| Key::Signed(i64::from(v));
+        visit_i64(i64) => Key::Signed;
+        visit_i128(i128);
+        visit_u8(u8) => |v| Key::Unsigned(u64::from(v));
+        visit_u16(u16) => |v| Key::Unsigned(u64::from(v));
+        visit_u32(u32) => |v| Key::Unsigned(u64::from(v));
+        visit_u64(u64) => Key::Unsigned;
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str) => Key::inline;
+        visit_borrowed_str(&'de str) => Key::Borrowed;
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if let Some(key) = self.key {
+            key.set(Key::inline(&v));
+        }
+        self.inner.visit_string(v)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_byte_buf(v)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserializer = self.with(deserializer);
+        self.inner.visit_some(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserializer = self.with(deserializer);
+        self.inner.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.inner.visit_seq(Seq {
+            seq,
+            segment: self.segment,
+            index: 0,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.inner.visit_map(Map {
+            map,
+            segment: self.segment,
+            key: Cell::new(Key::Unknown),
+        })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let data = self.with(data);
+        self.inner.visit_enum(data)
+    }
+}
+
+impl<'p, 'de, A> EnumAccess<'de> for Wrap<'p, 'de, A>
+where
+    A: EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = Wrap<'p, 'de, A::Variant>;
+
+    fn variant_seed<S>(self, seed: S) -> Result<(S::Value, Self::Variant), Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let (value, variant) = self.inner.variant_seed(seed)?;
+        Ok((value, Wrap::new(variant, self.segment, None)))
+    }
+}
+
+impl<'p, 'de, A> VariantAccess<'de> for Wrap<'p, 'de, A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<S>(self, seed: S) -> Result<S::Value, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let seed = self.with(seed);
+        self.inner.newtype_variant_seed(seed)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = self.with(visitor);
+        self.inner.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = self.with(visitor);
+        self.inner.struct_variant(fields, visitor)
+    }
+}
+
+/// Sequence accessor giving each element its index.
+struct Seq<'p, 'de, A> {
+    seq: A,
+    segment: &'p Segment<'p, 'de>,
+    index: usize,
+}
+
+impl<'p, 'de, A> SeqAccess<'de> for Seq<'p, 'de, A>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let index = self.index;
+        self.index += 1;
+        self.seq.next_element_seed(Element {
+            seed,
+            segment: Segment::Index(self.segment, index),
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.seq.size_hint()
+    }
+}
+
+/// Map accessor capturing the keys, and giving each value its key.
+struct Map<'p, 'de, A> {
+    map: A,
+    segment: &'p Segment<'p, 'de>,
+    key: Cell<Key<'de>>,
+}
+
+impl<'p, 'de, A> MapAccess<'de> for Map<'p, 'de, A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.key.set(Key::Unknown);
+        self.map
+            .next_key_seed(Wrap::new(seed, self.segment, Some(&self.key)))
+    }
+
+    fn next_value_seed<S>(&mut self, seed: S) -> Result<S::Value, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(Element {
+            seed,
+            segment: Segment::Key(self.segment, self.key.get()),
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
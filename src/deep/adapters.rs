@@ -0,0 +1,256 @@
+use core::{fmt, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// An adapter forwarding to `A` only the items matching the predicate `P`
+///
+/// ```rust
+/// use serde_deser_iter::deep::{Filter, FilterFn, Map, MapFn, StreamSeqDeser, Sum};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Order {
+///     price: u64,
+///     cancelled: bool,
+/// }
+///
+/// struct NotCancelled;
+///
+/// impl FilterFn for NotCancelled {
+///     type Item = Order;
+///
+///     fn f(order: &Order) -> bool {
+///         !order.cancelled
+///     }
+/// }
+///
+/// struct Price;
+///
+/// impl MapFn for Price {
+///     type Input = Order;
+///     type Output = u64;
+///
+///     fn f(order: Order) -> u64 {
+///         order.price
+///     }
+/// }
+///
+/// #[derive(serde::Deserialize)]
+/// struct Data {
+///     orders: StreamSeqDeser<Filter<NotCancelled, Map<Price, Sum<u64>>>>,
+///     refunds: StreamSeqDeser<Sum<u64>>,
+/// }
+///
+/// let json = r#"{
+///     "orders": [
+///         {"price": 10, "cancelled": false},
+///         {"price": 20, "cancelled": true},
+///         {"price": 30, "cancelled": false}
+///     ],
+///     "refunds": [5, 2]
+/// }"#;
+/// let data: Data = serde_json::from_str(json).unwrap();
+/// assert_eq!(*data.orders - *data.refunds, 33);
+/// ```
+pub struct Filter<P, A> {
+    marker: PhantomData<(P, A)>,
+}
+
+/// Predicate used by [`Filter`]
+pub trait FilterFn {
+    /// The Item deserialized from the sequences
+    type Item;
+
+    /// Core predicate, only matching items are forwarded
+    fn f(item: &Self::Item) -> bool;
+}
+
+impl<P, A> Aggregator for Filter<P, A>
+where
+    P: FilterFn,
+    A: Aggregator<Item = P::Item>,
+{
+    type Acc = A::Acc;
+
+    type Item = A::Item;
+
+    type Break = A::Break;
+
+    type Value = A::Value;
+
+    const OPTIONS: Options = A::OPTIONS;
+
+    fn init() -> Self::Acc {
+        A::init()
+    }
+
+    fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        if P::f(&item) {
+            A::try_fold(acc, item)
+        } else {
+            ControlFlow::Continue(acc)
+        }
+    }
+
+    fn finalize(x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        A::finalize(x)
+    }
+
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        A::error(state)
+    }
+}
+
+/// An adapter transforming the items with `M` before forwarding them to `A`
+pub struct Map<M, A> {
+    marker: PhantomData<(M, A)>,
+}
+
+/// Function used by [`Map`]
+pub trait MapFn {
+    /// The Item deserialized from the sequences
+    type Input;
+    /// The Item forwarded to the inner aggregator
+    type Output;
+
+    /// Core mapping function
+    fn f(item: Self::Input) -> Self::Output;
+}
+
+impl<M, A> Aggregator for Map<M, A>
+where
+    M: MapFn,
+    A: Aggregator<Item = M::Output>,
+{
+    type Acc = A::Acc;
+
+    type Item = M::Input;
+
+    type Break = A::Break;
+
+    type Value = A::Value;
+
+    const OPTIONS: Options = A::OPTIONS;
+
+    fn init() -> Self::Acc {
+        A::init()
+    }
+
+    fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        A::try_fold(acc, M::f(item))
+    }
+
+    fn finalize(x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        A::finalize(x)
+    }
+
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        A::error(state)
+    }
+}
+
+/// An adapter transforming the items with `M` and forwarding the non-`None` results to `A`
+pub struct FilterMap<M, A> {
+    marker: PhantomData<(M, A)>,
+}
+
+/// Function used by [`FilterMap`]
+pub trait FilterMapFn {
+    /// The Item deserialized from the sequences
+    type Input;
+    /// The Item forwarded to the inner aggregator
+    type Output;
+
+    /// Core mapping function, `None` results are skipped
+    fn f(item: Self::Input) -> Option<Self::Output>;
+}
+
+impl<M, A> Aggregator for FilterMap<M, A>
+where
+    M: FilterMapFn,
+    A: Aggregator<Item = M::Output>,
+{
+    type Acc = A::Acc;
+
+    type Item = M::Input;
+
+    type Break = A::Break;
+
+    type Value = A::Value;
+
+    const OPTIONS: Options = A::OPTIONS;
+
+    fn init() -> Self::Acc {
+        A::init()
+    }
+
+    fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        match M::f(item) {
+            Some(item) => A::try_fold(acc, item),
+            None => ControlFlow::Continue(acc),
+        }
+    }
+
+    fn finalize(x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        A::finalize(x)
+    }
+
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        A::error(state)
+    }
+}
+
+/// An adapter running `H` on each item before forwarding it to `A`
+pub struct Inspect<H, A> {
+    marker: PhantomData<(H, A)>,
+}
+
+/// Function used by [`Inspect`]
+pub trait InspectFn {
+    /// The Item deserialized from the sequences
+    type Item;
+
+    /// Core function
+    fn f(item: &Self::Item);
+}
+
+impl<H, A> Aggregator for Inspect<H, A>
+where
+    H: InspectFn,
+    A: Aggregator<Item = H::Item>,
+{
+    type Acc = A::Acc;
+
+    type Item = A::Item;
+
+    type Break = A::Break;
+
+    type Value = A::Value;
+
+    const OPTIONS: Options = A::OPTIONS;
+
+    fn init() -> Self::Acc {
+        A::init()
+    }
+
+    fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        H::f(&item);
+        A::try_fold(acc, item)
+    }
+
+    fn finalize(x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        A::finalize(x)
+    }
+
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        A::error(state)
+    }
+}
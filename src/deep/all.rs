@@ -0,0 +1,53 @@
+use core::{marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// A wrapper testing whether all items match a predicate
+pub struct All<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for testing whether all items match
+pub trait AllAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Core predicate
+    fn f(item: Self::Item) -> bool;
+}
+
+impl<I> Aggregator for All<I>
+where
+    I: AllAggregator,
+{
+    type Acc = ();
+
+    type Item = I::Item;
+
+    type Break = ();
+
+    type Value = bool;
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    #[allow(clippy::unused_unit)]
+    fn init() -> Self::Acc {
+        ()
+    }
+
+    fn try_fold((): Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        if I::f(item) {
+            ControlFlow::Continue(())
+        } else {
+            ControlFlow::Break(())
+        }
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        f.is_continue()
+    }
+}
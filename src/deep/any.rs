@@ -0,0 +1,50 @@
+use core::{marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// A wrapper testing whether any item matches a predicate
+pub struct Any<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for testing whether any item matches
+pub trait AnyAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Core predicate
+    fn f(item: Self::Item) -> bool;
+}
+
+impl<I> Aggregator for Any<I>
+where
+    I: AnyAggregator,
+{
+    type Acc = ();
+
+    type Item = I::Item;
+
+    type Break = ();
+
+    type Value = bool;
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    fn init() -> Self::Acc {}
+
+    fn try_fold((): Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        if I::f(item) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        f.is_break()
+    }
+}
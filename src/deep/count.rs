@@ -0,0 +1,36 @@
+use core::{convert::Infallible, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+
+/// A wrapper counting the items of type `T`
+///
+/// Use [`IgnoredAny`](serde::de::IgnoredAny) as item type to count items
+/// without deserializing them.
+pub struct Count<T> {
+    marker: PhantomData<T>,
+}
+
+impl<T> Aggregator for Count<T> {
+    type Acc = usize;
+
+    type Item = T;
+
+    type Break = Infallible;
+
+    type Value = usize;
+
+    fn init() -> Self::Acc {
+        0
+    }
+
+    fn try_fold(acc: Self::Acc, _item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        ControlFlow::Continue(acc + 1)
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(acc) => acc,
+            ControlFlow::Break(_) => unreachable!(),
+        }
+    }
+}
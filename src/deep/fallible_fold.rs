@@ -0,0 +1,135 @@
+use core::{fmt, marker::PhantomData, ops::ControlFlow};
+
+use serde::{Deserialize, Deserializer};
+
+use super::{Aggregator, StreamSeqDeser};
+use crate::Options;
+
+/// A wrapper for a folding aggregator which may fail the deserialization
+///
+/// ```
+/// use core::ops::ControlFlow;
+/// use std::collections::HashSet;
+///
+/// use serde_deser_iter::deep::{FallibleFold, FallibleFoldAggregator, StreamSeqDeser};
+///
+/// struct UniqueIds;
+///
+/// impl FallibleFoldAggregator for UniqueIds {
+///     type Item = u32;
+///     type Acc = HashSet<u32>;
+///     type Break = ();
+///     type Error = String;
+///
+///     fn init() -> Self::Acc {
+///         HashSet::new()
+///     }
+///
+///     fn f(mut acc: HashSet<u32>, id: u32) -> Result<ControlFlow<(), HashSet<u32>>, String> {
+///         if acc.insert(id) {
+///             Ok(ControlFlow::Continue(acc))
+///         } else {
+///             Err(format!("duplicate id {id}"))
+///         }
+///     }
+/// }
+///
+/// #[derive(serde::Deserialize)]
+/// struct Data {
+///     ids: StreamSeqDeser<FallibleFold<UniqueIds>>,
+/// }
+///
+/// let data: Result<Data, _> = serde_json::from_str(r#"{"ids": [1, 2, 1, 3]}"#);
+/// assert_eq!(
+///     data.err().unwrap().to_string(),
+///     "duplicate id 1 at line 1 column 18"
+/// );
+/// ```
+pub struct FallibleFold<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for folding aggregation which may fail the deserialization
+///
+/// Returning an error fails the whole deserialization with it, through
+/// [`serde::de::Error::custom`], whereas returning [`ControlFlow::Break`] only stops
+/// the aggregation early.
+pub trait FallibleFoldAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+    /// The accumulator type
+    type Acc;
+    /// The early return type
+    type Break;
+    /// The error type
+    type Error: fmt::Display;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Initial value of the accumulator
+    fn init() -> Self::Acc;
+
+    /// Core folding function
+    fn f(
+        acc: Self::Acc,
+        item: Self::Item,
+    ) -> Result<ControlFlow<Self::Break, Self::Acc>, Self::Error>;
+}
+
+impl<I> Aggregator for FallibleFold<I>
+where
+    I: FallibleFoldAggregator,
+{
+    type Acc = I::Acc;
+
+    type Item = I::Item;
+
+    type Break = Result<I::Break, I::Error>;
+
+    type Value = ControlFlow<I::Break, I::Acc>;
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    fn init() -> Self::Acc {
+        I::init()
+    }
+
+    fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        match I::f(acc, item) {
+            Ok(ControlFlow::Continue(acc)) => ControlFlow::Continue(acc),
+            Ok(ControlFlow::Break(clot_break)) => ControlFlow::Break(Ok(clot_break)),
+            Err(e) => ControlFlow::Break(Err(e)),
+        }
+    }
+
+    fn finalize(x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match x {
+            ControlFlow::Continue(acc) => ControlFlow::Continue(acc),
+            ControlFlow::Break(Ok(clot_break)) => ControlFlow::Break(clot_break),
+            ControlFlow::Break(Err(_)) => unreachable!("failed aggregations are not finalized"),
+        }
+    }
+
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        match state {
+            ControlFlow::Break(Err(e)) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Deserialize a sequence to the value folded by `I`, which may return early or fail.
+///
+/// Meant to be used with `#[serde(deserialize_with = "serde_deser_iter::deep::fallible_fold::<Imp, _>")]`,
+/// see [`aggregate`](super::aggregate).
+pub fn fallible_fold<'de, I, D>(deserializer: D) -> Result<ControlFlow<I::Break, I::Acc>, D::Error>
+where
+    I: FallibleFoldAggregator,
+    I::Item: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    StreamSeqDeser::<FallibleFold<I>>::deserialize(deserializer).map(StreamSeqDeser::into_inner)
+}
@@ -1,6 +1,9 @@
 use core::{marker::PhantomData, ops::ControlFlow};
 
-use super::Aggregator;
+use serde::{Deserialize, Deserializer};
+
+use super::{Aggregator, StreamSeqDeser};
+use crate::Options;
 
 /// A wrapper for a searching aggregator
 pub struct Find<I> {
@@ -12,6 +15,9 @@ pub trait FindAggregator {
     /// The Item deserialized from the sequences
     type Item;
 
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
     /// Core searching function
     fn f(item: &Self::Item) -> bool;
 }
@@ -28,6 +34,8 @@ where
 
     type Value = Option<I::Item>;
 
+    const OPTIONS: Options = I::OPTIONS;
+
     #[allow(clippy::unused_unit)]
     fn init() -> Self::Acc {
         ()
@@ -48,3 +56,16 @@ where
         }
     }
 }
+
+/// Deserialize a sequence to the first item matching `I`.
+///
+/// Meant to be used with `#[serde(deserialize_with = "serde_deser_iter::deep::find::<Imp, _>")]`,
+/// see [`aggregate`](super::aggregate).
+pub fn find<'de, I, D>(deserializer: D) -> Result<Option<I::Item>, D::Error>
+where
+    I: FindAggregator,
+    I::Item: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    StreamSeqDeser::<Find<I>>::deserialize(deserializer).map(StreamSeqDeser::into_inner)
+}
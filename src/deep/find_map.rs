@@ -0,0 +1,57 @@
+use core::{marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// A wrapper returning the first non-`None` result of a function applied to the items
+pub struct FindMap<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for searching and mapping aggregation
+pub trait FindMapAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+    /// The type returned when an item is found
+    type Output;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Core searching and mapping function
+    fn f(item: Self::Item) -> Option<Self::Output>;
+}
+
+impl<I> Aggregator for FindMap<I>
+where
+    I: FindMapAggregator,
+{
+    type Acc = ();
+
+    type Item = I::Item;
+
+    type Break = I::Output;
+
+    type Value = Option<I::Output>;
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    #[allow(clippy::unused_unit)]
+    fn init() -> Self::Acc {
+        ()
+    }
+
+    fn try_fold((): Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        match I::f(item) {
+            Some(res) => ControlFlow::Break(res),
+            None => ControlFlow::Continue(()),
+        }
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(()) => None,
+            ControlFlow::Break(res) => Some(res),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use core::{convert::Infallible, marker::PhantomData, ops::ControlFlow};
 
-use super::Aggregator;
+use serde::{Deserialize, Deserializer};
+
+use super::{Aggregator, StreamSeqDeser};
+use crate::Options;
 
 /// A wrapper for a folding aggregator
 pub struct Fold<I> {
@@ -14,6 +17,9 @@ pub trait FoldAggregator {
     /// The accumulator type
     type Acc;
 
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
     /// Initial value of the accumulator
     fn init() -> Self::Acc;
 
@@ -33,6 +39,8 @@ where
 
     type Value = I::Acc;
 
+    const OPTIONS: Options = I::OPTIONS;
+
     fn init() -> Self::Acc {
         I::init()
     }
@@ -48,3 +56,51 @@ where
         }
     }
 }
+
+/// Deserialize a sequence to the value folded by `I`.
+///
+/// Meant to be used with `#[serde(deserialize_with = "serde_deser_iter::deep::fold::<Imp, _>")]`,
+/// see [`aggregate`](super::aggregate).
+///
+/// ```rust
+/// use std::collections::HashSet;
+///
+/// #[derive(serde::Deserialize)]
+/// struct DataEntry {
+///     subscribed_to: Vec<String>,
+/// }
+///
+/// struct Imp;
+///
+/// impl serde_deser_iter::deep::FoldAggregator for Imp {
+///     type Item = DataEntry;
+///     type Acc = HashSet<String>;
+///
+///     fn init() -> Self::Acc {
+///         HashSet::new()
+///     }
+///
+///     fn f(mut acc: HashSet<String>, entry: DataEntry) -> HashSet<String> {
+///         acc.extend(entry.subscribed_to);
+///         acc
+///     }
+/// }
+///
+/// #[derive(serde::Deserialize)]
+/// struct Data {
+///     #[serde(deserialize_with = "serde_deser_iter::deep::fold::<Imp, _>")]
+///     result: HashSet<String>,
+/// }
+///
+/// let json = r#"{"result": [{"subscribed_to": ["rust"]}, {"subscribed_to": ["rust", "sticks"]}]}"#;
+/// let data: Data = serde_json::from_str(json).unwrap();
+/// assert_eq!(data.result.len(), 2);
+/// ```
+pub fn fold<'de, I, D>(deserializer: D) -> Result<I::Acc, D::Error>
+where
+    I: FoldAggregator,
+    I::Item: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    StreamSeqDeser::<Fold<I>>::deserialize(deserializer).map(StreamSeqDeser::into_inner)
+}
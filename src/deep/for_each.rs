@@ -1,6 +1,9 @@
 use core::{convert::Infallible, marker::PhantomData, ops::ControlFlow};
 
-use super::Aggregator;
+use serde::{Deserialize, Deserializer};
+
+use super::{Aggregator, StreamSeqDeser};
+use crate::Options;
 
 /// A wrapper to apply a function to each element
 pub struct ForEach<I> {
@@ -12,6 +15,9 @@ pub trait ForEachAggregator {
     /// The Item deserialized from the sequences
     type Item;
 
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
     /// Core function
     fn f(item: Self::Item);
 }
@@ -28,6 +34,8 @@ where
 
     type Value = ();
 
+    const OPTIONS: Options = I::OPTIONS;
+
     #[allow(clippy::unused_unit)]
     fn init() -> Self::Acc {
         ()
@@ -45,3 +53,16 @@ where
         }
     }
 }
+
+/// Run `I` on each item of a sequence.
+///
+/// Meant to be used with `#[serde(deserialize_with = "serde_deser_iter::deep::for_each::<Imp, _>")]`,
+/// see [`aggregate`](super::aggregate).
+pub fn for_each<'de, I, D>(deserializer: D) -> Result<(), D::Error>
+where
+    I: ForEachAggregator,
+    I::Item: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    StreamSeqDeser::<ForEach<I>>::deserialize(deserializer).map(StreamSeqDeser::into_inner)
+}
@@ -0,0 +1,239 @@
+use core::{fmt, marker::PhantomData, ops::ControlFlow};
+
+use serde::{de::DeserializeSeed, Deserialize, Deserializer};
+
+use super::{Aggregator, Vis};
+use crate::Options;
+
+/// The instance-based counterpart of [`Aggregator`].
+///
+/// Contrary to [`Aggregator`], whose functions are all associated functions,
+/// this trait works on an instance of the implementing type, which can therefore
+/// carry runtime parameters (a threshold, a number of items to keep...).
+///
+/// It can be used:
+/// - through [`Instance`], which implements [`Aggregator`] using [`Default`] to
+///   build the instance, and can then be given to [`StreamSeqDeser`](super::StreamSeqDeser);
+/// - through [`InstanceSeed`], to deserialize a sequence with a given instance.
+///
+/// Conversely, [`Static`] turns an [`Aggregator`] into an instance.
+///
+/// ```
+/// use core::{convert::Infallible, ops::ControlFlow};
+/// use serde::de::DeserializeSeed;
+/// use serde_deser_iter::deep::{AggregatorInstance, AtField, InstanceSeed};
+///
+/// struct AboveThreshold {
+///     threshold: u32,
+/// }
+///
+/// impl AggregatorInstance for AboveThreshold {
+///     type Acc = usize;
+///     type Item = u32;
+///     type Break = Infallible;
+///     type Value = usize;
+///
+///     fn init(&mut self) -> usize {
+///         0
+///     }
+///
+///     fn try_fold(&mut self, count: usize, item: u32) -> ControlFlow<Infallible, usize> {
+///         ControlFlow::Continue(if item > self.threshold { count + 1 } else { count })
+///     }
+///
+///     fn finalize(&mut self, x: ControlFlow<Infallible, usize>) -> usize {
+///         match x {
+///             ControlFlow::Continue(count) => count,
+///             ControlFlow::Break(infallible) => match infallible {},
+///         }
+///     }
+/// }
+///
+/// let mut json_deserializer = serde_json::Deserializer::from_str(r#"{"result": [1, 5, 3]}"#);
+/// let seed = InstanceSeed::new(AboveThreshold { threshold: 2 });
+/// let count = AtField::new("result", seed)
+///     .deserialize(&mut json_deserializer)
+///     .unwrap();
+/// assert_eq!(count, 2);
+/// ```
+pub trait AggregatorInstance {
+    /// The accumulator type
+    type Acc;
+    /// The type of item deserialized from the sequence
+    type Item;
+    /// The type of early return value
+    type Break;
+    /// The final agregated type
+    type Value;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Initial value of the accumulator
+    fn init(&mut self) -> Self::Acc;
+
+    /// The core folding function
+    fn try_fold(&mut self, acc: Self::Acc, item: Self::Item)
+        -> ControlFlow<Self::Break, Self::Acc>;
+
+    /// A finaliser obtaining the definitive aggregated value.
+    fn finalize(&mut self, x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value;
+
+    /// The hard error of the aggregation state, if any, see [`Aggregator::error`].
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        let _ = state;
+        None
+    }
+}
+
+/// An adapter using an [`Aggregator`] as an [`AggregatorInstance`]
+pub struct Static<A> {
+    marker: PhantomData<A>,
+}
+
+impl<A> Static<A> {
+    /// Create the instance.
+    pub fn new() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A> Default for Static<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> AggregatorInstance for Static<A>
+where
+    A: Aggregator,
+{
+    type Acc = A::Acc;
+
+    type Item = A::Item;
+
+    type Break = A::Break;
+
+    type Value = A::Value;
+
+    const OPTIONS: Options = A::OPTIONS;
+
+    fn init(&mut self) -> Self::Acc {
+        A::init()
+    }
+
+    fn try_fold(
+        &mut self,
+        acc: Self::Acc,
+        item: Self::Item,
+    ) -> ControlFlow<Self::Break, Self::Acc> {
+        A::try_fold(acc, item)
+    }
+
+    fn finalize(&mut self, x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        A::finalize(x)
+    }
+
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        A::error(state)
+    }
+}
+
+/// An adapter using an [`AggregatorInstance`] as an [`Aggregator`], building the instance
+/// with [`Default`]
+///
+/// The instance is carried along the accumulator.
+pub struct Instance<T> {
+    marker: PhantomData<T>,
+}
+
+impl<T> Aggregator for Instance<T>
+where
+    T: AggregatorInstance + Default,
+{
+    type Acc = (T, T::Acc);
+
+    type Item = T::Item;
+
+    type Break = (T, T::Break);
+
+    type Value = T::Value;
+
+    const OPTIONS: Options = T::OPTIONS;
+
+    fn init() -> Self::Acc {
+        let mut instance = T::default();
+        let acc = instance.init();
+        (instance, acc)
+    }
+
+    fn try_fold(
+        (mut instance, acc): Self::Acc,
+        item: Self::Item,
+    ) -> ControlFlow<Self::Break, Self::Acc> {
+        match instance.try_fold(acc, item) {
+            ControlFlow::Continue(acc) => ControlFlow::Continue((instance, acc)),
+            ControlFlow::Break(clot_break) => ControlFlow::Break((instance, clot_break)),
+        }
+    }
+
+    fn finalize(x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match x {
+            ControlFlow::Continue((mut instance, acc)) => {
+                instance.finalize(ControlFlow::Continue(acc))
+            }
+            ControlFlow::Break((mut instance, clot_break)) => {
+                instance.finalize(ControlFlow::Break(clot_break))
+            }
+        }
+    }
+
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        match state {
+            ControlFlow::Continue((_, acc)) => T::error(ControlFlow::Continue(acc)),
+            ControlFlow::Break((_, clot_break)) => T::error(ControlFlow::Break(clot_break)),
+        }
+    }
+}
+
+/// A seed deserializing a sequence using an [`AggregatorInstance`].
+///
+/// Its value is the aggregated value.
+pub struct InstanceSeed<T> {
+    instance: T,
+}
+
+impl<T> InstanceSeed<T> {
+    /// Aggregate the sequence using `instance`.
+    pub fn new(instance: T) -> Self {
+        Self { instance }
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for InstanceSeed<T>
+where
+    T: AggregatorInstance,
+    T::Item: Deserialize<'de>,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut instance = self.instance;
+        let init = instance.init();
+        let mut vis = Vis::new(init, |acc, item| instance.try_fold(acc, item), T::OPTIONS);
+        vis.error = T::error;
+        let fin = T::OPTIONS.shape.deserialize(deserializer, vis)?;
+        Ok(instance.finalize(fin))
+    }
+}
@@ -0,0 +1,33 @@
+use core::{convert::Infallible, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+
+/// A wrapper keeping the last item of type `T`
+pub struct Last<T> {
+    marker: PhantomData<T>,
+}
+
+impl<T> Aggregator for Last<T> {
+    type Acc = Option<T>;
+
+    type Item = T;
+
+    type Break = Infallible;
+
+    type Value = Option<T>;
+
+    fn init() -> Self::Acc {
+        None
+    }
+
+    fn try_fold(_acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        ControlFlow::Continue(Some(item))
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(acc) => acc,
+            ControlFlow::Break(_) => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use core::{
+    fmt,
+    ops::{ControlFlow, Deref},
+};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize,
+};
+
+use super::{as_ref, reject_abort, Aggregator, Vis};
+#[cfg(feature = "alloc")]
+use crate::Skipped;
+use crate::{
+    elements::{self, Elements},
+    Drain,
+};
+
+/// The entry point for deep deserialization of maps.
+///
+/// This is the counterpart of [`StreamSeqDeser`](super::StreamSeqDeser) for maps: it can be
+/// used anywhere a map would be encountered, and its aggregator receives the entries
+/// of the map as `(key, value)` pairs, i.e. its item type must be a pair.
+///
+/// ```rust
+/// use std::collections::HashSet;
+///
+/// #[derive(serde::Deserialize)]
+/// struct User {
+///     age: u32,
+/// }
+///
+/// struct Imp;
+///
+/// impl serde_deser_iter::deep::FoldAggregator for Imp {
+///     type Item = (u64, User);
+///     type Acc = HashSet<u64>;
+///
+///     fn init() -> Self::Acc {
+///         HashSet::new()
+///     }
+///
+///     fn f(mut acc: HashSet<u64>, (id, user): (u64, User)) -> HashSet<u64> {
+///         if user.age >= 18 {
+///             acc.insert(id);
+///         }
+///         acc
+///     }
+/// }
+///
+/// #[derive(serde::Deserialize)]
+/// struct Data {
+///     users_by_id: serde_deser_iter::deep::StreamMapDeser<serde_deser_iter::deep::Fold<Imp>>,
+/// }
+///
+/// let json = r#"{"users_by_id": {"17": {"age": 31}, "42": {"age": 12}}}"#;
+/// let data: Data = serde_json::from_str(json).unwrap();
+/// assert_eq!(data.users_by_id.into_inner(), HashSet::from([17]));
+/// ```
+///
+/// The [`OPTIONS`](super::Aggregator::OPTIONS) of the aggregator apply to the entries as to
+/// the items of a sequence, an entry being located by its index in the map:
+///
+/// ```rust
+/// use serde_deser_iter::{
+///     deep::{Fold, FoldAggregator, StreamMapDeser},
+///     Options,
+/// };
+///
+/// struct Sum;
+///
+/// impl FoldAggregator for Sum {
+///     type Item = (String, u32);
+///     type Acc = u32;
+///
+///     const OPTIONS: Options = Options::new().error_context(true);
+///
+///     fn init() -> u32 {
+///         0
+///     }
+///
+///     fn f(acc: u32, (_, value): (String, u32)) -> u32 {
+///         acc + value
+///     }
+/// }
+///
+/// let json = r#"{"a": 1, "b": "x"}"#;
+/// let error = serde_json::from_str::<StreamMapDeser<Fold<Sum>>>(json)
+///     .err()
+///     .unwrap();
+/// assert!(error.to_string().starts_with("[1]: invalid type"));
+/// ```
+pub struct StreamMapDeser<I: Aggregator> {
+    value: I::Value,
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    skipped: elements::Skipped,
+}
+
+impl<I: Aggregator> Deref for StreamMapDeser<I> {
+    type Target = I::Value;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<I: Aggregator> StreamMapDeser<I> {
+    /// Reference to the aggregated value
+    pub fn value(&self) -> &I::Value {
+        &self.value
+    }
+
+    /// Take ownership of the aggregated value
+    pub fn into_inner(self) -> I::Value {
+        self.value
+    }
+
+    /// The entries skipped according to the [`OnItemError`](crate::OnItemError) policy of the
+    /// aggregator's options.
+    ///
+    /// ```rust
+    /// use serde_deser_iter::{
+    ///     deep::{Fold, FoldAggregator, StreamMapDeser},
+    ///     OnItemError, Options,
+    /// };
+    ///
+    /// struct Sum;
+    ///
+    /// impl FoldAggregator for Sum {
+    ///     type Item = (String, u32);
+    ///     type Acc = u32;
+    ///
+    ///     const OPTIONS: Options = Options::new().on_item_error(OnItemError::Collect);
+    ///
+    ///     fn init() -> u32 {
+    ///         0
+    ///     }
+    ///
+    ///     fn f(acc: u32, (_, value): (String, u32)) -> u32 {
+    ///         acc + value
+    ///     }
+    /// }
+    ///
+    /// let json = r#"{"a": 1, "b": "x", "c": 3}"#;
+    /// let sum: StreamMapDeser<Fold<Sum>> = serde_json::from_str(json).unwrap();
+    /// assert_eq!(*sum.value(), 4);
+    /// assert_eq!(sum.skipped().count(), 1);
+    /// assert_eq!(sum.skipped().items()[0].index(), 1);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn skipped(&self) -> &Skipped {
+        &self.skipped
+    }
+}
+
+struct MapVis<T>(T);
+
+impl<'de, 'a, Acc, K, V, Break, F> Visitor<'de> for MapVis<Vis<'a, Acc, (K, V), Break, F>>
+where
+    F: FnMut(Acc, (K, V)) -> ControlFlow<Break, Acc>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = ControlFlow<Break, Acc>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(mut self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut acc = self.0.init;
+        let mut elements = Elements::new(self.0.options, self.0.skipped);
+        while let Some(entry) = elements.next_entry(&mut map)? {
+            let state = (self.0.f)(acc, entry);
+            if let Some(e) = (self.0.error)(as_ref(&state)) {
+                return Err(elements.error(e));
+            }
+            match state {
+                ControlFlow::Continue(new_acc) => acc = new_acc,
+                ControlFlow::Break(clot_break) => {
+                    if let (Drain::Abort, Some(aborted)) = (self.0.options.drain, self.0.aborted) {
+                        aborted.0 = Some(clot_break);
+                        return Err(Drain::abort_error());
+                    }
+                    self.0.options.drain.drain_map::<K, V, _>(&mut map)?;
+                    return Ok(ControlFlow::Break(clot_break));
+                }
+            }
+        }
+        Ok(ControlFlow::Continue(acc))
+    }
+}
+
+impl<'de, I, K, V> Deserialize<'de> for StreamMapDeser<I>
+where
+    I: Aggregator<Item = (K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        reject_abort(I::OPTIONS)?;
+        let mut skipped = elements::Skipped::new();
+        let mut vis = Vis::new(I::init(), I::try_fold, I::OPTIONS);
+        vis.error = I::error;
+        vis.skipped = Some(&mut skipped);
+        let fin = deserializer.deserialize_map(MapVis(vis))?;
+        Ok(Self {
+            value: I::finalize(fin),
+            skipped,
+        })
+    }
+}
+
+/// Deserialize a map to the value aggregated by `I`.
+///
+/// This is the counterpart of [`aggregate`](super::aggregate) for maps, to be used as
+/// `#[serde(deserialize_with = "serde_deser_iter::deep::aggregate_map::<Agg, _, _, _>")]`.
+pub fn aggregate_map<'de, I, K, V, D>(deserializer: D) -> Result<I::Value, D::Error>
+where
+    I: Aggregator<Item = (K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    StreamMapDeser::<I>::deserialize(deserializer).map(StreamMapDeser::into_inner)
+}
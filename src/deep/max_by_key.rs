@@ -0,0 +1,57 @@
+use core::{convert::Infallible, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// A wrapper returning the item with the maximum key (the last one in case of equality)
+pub struct MaxByKey<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for maximum aggregation
+pub trait MaxByKeyAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+    /// The key the items are compared by
+    type Key: Ord;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Key extraction function
+    fn f(item: &Self::Item) -> Self::Key;
+}
+
+impl<I> Aggregator for MaxByKey<I>
+where
+    I: MaxByKeyAggregator,
+{
+    type Acc = Option<(I::Key, I::Item)>;
+
+    type Item = I::Item;
+
+    type Break = Infallible;
+
+    type Value = Option<I::Item>;
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    fn init() -> Self::Acc {
+        None
+    }
+
+    fn try_fold(max: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        let key = I::f(&item);
+        match max {
+            Some((max_key, max)) if max_key > key => ControlFlow::Continue(Some((max_key, max))),
+            _ => ControlFlow::Continue(Some((key, item))),
+        }
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(max) => max.map(|(_, item)| item),
+            ControlFlow::Break(_) => unreachable!(),
+        }
+    }
+}
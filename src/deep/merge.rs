@@ -0,0 +1,275 @@
+#[cfg(feature = "std")]
+use core::ops::ControlFlow;
+use core::{convert::Infallible, iter};
+
+#[cfg(feature = "std")]
+use serde::{de::DeserializeSeed, Deserialize, Deserializer};
+
+#[cfg(feature = "std")]
+use super::StreamSeqSeed;
+use super::{
+    Aggregator, Count, Filter, FilterFn, FilterMap, FilterMapFn, Fold, FoldAggregator, Inspect,
+    InspectFn, Map, MapFn, MaxByKey, MaxByKeyAggregator, MinByKey, MinByKeyAggregator, Partition,
+    PartitionAggregator, Product, Sum,
+};
+#[cfg(feature = "std")]
+use crate::Drain;
+
+/// An [`Aggregator`] whose accumulators can be merged.
+///
+/// The items of a sequence can then be folded by several workers into their own
+/// accumulator, starting from [`Aggregator::init`], the accumulators being merged at the end
+/// (see `par_aggregate` with the `std` feature). The items are not dispatched in order, and
+/// the accumulators are merged in any order, except for the accumulators of several
+/// sources which are merged in the order of the sources, `a` coming before `b`.
+pub trait MergeableAggregator: Aggregator<Break = Infallible> {
+    /// Combine two accumulators into one.
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc;
+}
+
+/// Functions for folding aggregation whose accumulators can be merged, see
+/// [`MergeableAggregator`].
+pub trait MergeableFoldAggregator: FoldAggregator {
+    /// Combine two accumulators into one.
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc;
+}
+
+impl<I> MergeableAggregator for Fold<I>
+where
+    I: MergeableFoldAggregator,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        I::merge(a, b)
+    }
+}
+
+impl<T> MergeableAggregator for Count<T> {
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        a + b
+    }
+}
+
+impl<T> MergeableAggregator for Sum<T>
+where
+    T: iter::Sum,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        [a, b].into_iter().sum()
+    }
+}
+
+impl<T> MergeableAggregator for Product<T>
+where
+    T: iter::Product,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        [a, b].into_iter().product()
+    }
+}
+
+impl<I> MergeableAggregator for MinByKey<I>
+where
+    I: MinByKeyAggregator,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        match (a, b) {
+            (Some((key_a, a)), Some((key_b, _))) if key_a <= key_b => Some((key_a, a)),
+            (a, b) => b.or(a),
+        }
+    }
+}
+
+impl<I> MergeableAggregator for MaxByKey<I>
+where
+    I: MaxByKeyAggregator,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        match (a, b) {
+            (Some((key_a, a)), Some((key_b, _))) if key_a > key_b => Some((key_a, a)),
+            (a, b) => b.or(a),
+        }
+    }
+}
+
+impl<I> MergeableAggregator for Partition<I>
+where
+    I: PartitionAggregator,
+    I::Collection: IntoIterator<Item = I::Item>,
+{
+    fn merge(
+        (mut matching, mut others): Self::Acc,
+        (more_matching, more_others): Self::Acc,
+    ) -> Self::Acc {
+        matching.extend(more_matching);
+        others.extend(more_others);
+        (matching, others)
+    }
+}
+
+impl<P, A> MergeableAggregator for Filter<P, A>
+where
+    P: FilterFn,
+    A: MergeableAggregator<Item = P::Item>,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        A::merge(a, b)
+    }
+}
+
+impl<M, A> MergeableAggregator for Map<M, A>
+where
+    M: MapFn,
+    A: MergeableAggregator<Item = M::Output>,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        A::merge(a, b)
+    }
+}
+
+impl<M, A> MergeableAggregator for FilterMap<M, A>
+where
+    M: FilterMapFn,
+    A: MergeableAggregator<Item = M::Output>,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        A::merge(a, b)
+    }
+}
+
+impl<H, A> MergeableAggregator for Inspect<H, A>
+where
+    H: InspectFn,
+    A: MergeableAggregator<Item = H::Item>,
+{
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc {
+        A::merge(a, b)
+    }
+}
+
+/// Deserialize a sequence to the value aggregated by `I`, folding the items on a pool
+/// of worker threads.
+///
+/// The items are deserialized on the current thread, and dispatched by batches to one
+/// worker per available core, which is worth it when the work done on each item outweighs
+/// their deserialization. It is meant to be used with
+/// `#[serde(deserialize_with = "serde_deser_iter::deep::par_aggregate::<Agg, _>")]`,
+/// see [`aggregate`](super::aggregate).
+///
+/// ```
+/// use serde_deser_iter::deep::{Fold, FoldAggregator, MergeableFoldAggregator};
+///
+/// struct LongestName;
+///
+/// impl FoldAggregator for LongestName {
+///     type Item = String;
+///     type Acc = usize;
+///
+///     fn init() -> usize {
+///         0
+///     }
+///
+///     fn f(acc: usize, name: String) -> usize {
+///         acc.max(name.chars().count())
+///     }
+/// }
+///
+/// impl MergeableFoldAggregator for LongestName {
+///     fn merge(a: usize, b: usize) -> usize {
+///         a.max(b)
+///     }
+/// }
+///
+/// #[derive(serde::Deserialize)]
+/// struct Data {
+///     #[serde(deserialize_with = "serde_deser_iter::deep::par_aggregate::<Fold<LongestName>, _>")]
+///     names: usize,
+/// }
+///
+/// let data: Data = serde_json::from_str(r#"{"names": ["bob", "toby 🐶", "alice"]}"#).unwrap();
+/// assert_eq!(data.names, 6);
+/// ```
+///
+/// The [`error`](Aggregator::error) of the accumulators is checked after each item, and once
+/// they are merged:
+///
+/// ```
+/// use core::{convert::Infallible, fmt, ops::ControlFlow};
+///
+/// use serde_deser_iter::deep::{Aggregator, MergeableAggregator};
+///
+/// /// Sum of the items, which must not exceed 100.
+/// struct Budget;
+///
+/// impl Aggregator for Budget {
+///     type Acc = u32;
+///     type Item = u32;
+///     type Break = Infallible;
+///     type Value = u32;
+///
+///     fn init() -> u32 {
+///         0
+///     }
+///
+///     fn try_fold(acc: u32, item: u32) -> ControlFlow<Infallible, u32> {
+///         ControlFlow::Continue(acc + item)
+///     }
+///
+///     fn finalize(x: ControlFlow<Infallible, u32>) -> u32 {
+///         match x {
+///             ControlFlow::Continue(acc) => acc,
+///             ControlFlow::Break(never) => match never {},
+///         }
+///     }
+///
+///     fn error<'s>(state: ControlFlow<&'s Infallible, &'s u32>) -> Option<&'s dyn fmt::Display> {
+///         match state {
+///             ControlFlow::Continue(acc) if *acc > 100 => Some(&"over budget"),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// impl MergeableAggregator for Budget {
+///     fn merge(a: u32, b: u32) -> u32 {
+///         a + b
+///     }
+/// }
+///
+/// #[derive(serde::Deserialize)]
+/// struct Data {
+///     #[serde(deserialize_with = "serde_deser_iter::deep::par_aggregate::<Budget, _>")]
+///     costs: u32,
+/// }
+///
+/// let data: Data = serde_json::from_str(r#"{"costs": [40, 50]}"#).unwrap();
+/// assert_eq!(data.costs, 90);
+/// let error = serde_json::from_str::<Data>(r#"{"costs": [40, 50, 60]}"#).err().unwrap();
+/// assert!(error.to_string().starts_with("over budget"));
+/// ```
+#[cfg(feature = "std")]
+pub fn par_aggregate<'de, I, D>(deserializer: D) -> Result<I::Value, D::Error>
+where
+    I: MergeableAggregator,
+    I::Item: Deserialize<'de> + Send,
+    I::Acc: Send,
+    D: Deserializer<'de>,
+{
+    let acc = crate::par::par_fold(
+        I::init,
+        |acc, item| match I::try_fold(acc, item) {
+            ControlFlow::Continue(acc) => acc,
+            ControlFlow::Break(never) => match never {},
+        },
+        I::merge,
+        I::error,
+        |push| {
+            // `push` only breaks when the workers have failed, the rest of the input is then
+            // left unread.
+            StreamSeqSeed::new((), |(), item| push(item))
+                .with_options(I::OPTIONS.drain(Drain::Abort))
+                .deserialize(deserializer)
+                .map(drop)
+        },
+    )?;
+    Ok(I::finalize(ControlFlow::Continue(acc)))
+}
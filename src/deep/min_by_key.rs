@@ -0,0 +1,57 @@
+use core::{convert::Infallible, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// A wrapper returning the item with the minimum key (the first one in case of equality)
+pub struct MinByKey<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for minimum aggregation
+pub trait MinByKeyAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+    /// The key the items are compared by
+    type Key: Ord;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Key extraction function
+    fn f(item: &Self::Item) -> Self::Key;
+}
+
+impl<I> Aggregator for MinByKey<I>
+where
+    I: MinByKeyAggregator,
+{
+    type Acc = Option<(I::Key, I::Item)>;
+
+    type Item = I::Item;
+
+    type Break = Infallible;
+
+    type Value = Option<I::Item>;
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    fn init() -> Self::Acc {
+        None
+    }
+
+    fn try_fold(min: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        let key = I::f(&item);
+        match min {
+            Some((min_key, min)) if min_key <= key => ControlFlow::Continue(Some((min_key, min))),
+            _ => ControlFlow::Continue(Some((key, item))),
+        }
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(min) => min.map(|(_, item)| item),
+            ControlFlow::Break(_) => unreachable!(),
+        }
+    }
+}
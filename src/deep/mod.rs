@@ -5,7 +5,74 @@
 //! is used to define how the sequence is aggregated in to a final value. This type
 //! parameter must implement [`Aggregator`]. Though the user can provide its own implementation
 //! of it, it is advised to use the provided wrapper type constructors (and their
-//! associated traits) to better communicate intent and ease implementation.
+//! associated traits) to better communicate intent and ease implementation. Aggregators
+//! can also be combined with adapters such as [`Filter`] or [`Map`], which
+//! preprocess the items before forwarding them to an inner aggregator. Tuples of (up to six)
+//! aggregators are aggregators too, running all their members in a single pass.
+//!
+//! Maps are handled in the same way by [`StreamMapDeser`], whose aggregator receives
+//! `(key, value)` pairs.
+//!
+//! To keep the plain aggregated type for a field, rather than wrapping it in [`StreamSeqDeser`],
+//! the [`aggregate`] function (and its shorthands such as [`fold()`]) can be used with
+//! `#[serde(deserialize_with = "...")]`.
+//!
+//! With the `derive` feature, the `Aggregate` derive macro implements the
+//! wrappers' associated traits from plain functions, and the `ColumnAggregate` derive macro
+//! builds an aggregator computing several column summaries of a sequence of objects in one pass.
+//!
+//! Aggregators needing runtime parameters can implement [`AggregatorInstance`] instead.
+//!
+//! Aggregators whose accumulators can be merged implement [`MergeableAggregator`], and
+//! can then fold the items on several threads with the `std` feature.
+//!
+//! Besides returning early, an aggregator can fail the whole deserialization when it detects
+//! invalid data (see [`Aggregator::error`] and [`FallibleFold`]), in which case the parents
+//! deriving [`Deserialize`] fail with the error as well.
+//!
+//! When the aggregation needs to capture values from the environment, [`StreamSeqSeed`]
+//! provides the same machinery as a [`serde::de::DeserializeSeed`] driven by a closure,
+//! and [`AtField`] allows to reach it from a parent map.
+//!
+//! # Borrowed items
+//!
+//! Items may borrow from the input, such as `&'de str` when deserializing from an in-memory
+//! buffer with `serde_json::from_slice`. The aggregator then carries the lifetime of the
+//! borrow as a parameter, and the field is marked with `#[serde(borrow)]`:
+//!
+//! ```
+//! use core::marker::PhantomData;
+//!
+//! use serde_deser_iter::deep::{Fold, FoldAggregator, Last, StreamSeqDeser};
+//!
+//! struct TotalLen<'a>(PhantomData<&'a ()>);
+//!
+//! impl<'a> FoldAggregator for TotalLen<'a> {
+//!     type Item = &'a str;
+//!     type Acc = usize;
+//!
+//!     fn init() -> usize {
+//!         0
+//!     }
+//!
+//!     fn f(acc: usize, item: &'a str) -> usize {
+//!         acc + item.len()
+//!     }
+//! }
+//!
+//! #[derive(serde::Deserialize)]
+//! struct Data<'a> {
+//!     #[serde(borrow)]
+//!     names: StreamSeqDeser<Last<&'a str>>,
+//!     #[serde(borrow)]
+//!     channels: StreamSeqDeser<Fold<TotalLen<'a>>>,
+//! }
+//!
+//! let buffer = br#"{"names": ["bob", "alice"], "channels": ["rust", "hiking"]}"#;
+//! let data: Data = serde_json::from_slice(buffer).unwrap();
+//! assert_eq!(*data.names.value(), Some("alice"));
+//! assert_eq!(*data.channels.value(), 10);
+//! ```
 //!
 //! # Example
 //!
@@ -78,10 +145,17 @@ use core::{
 };
 
 use serde::{
-    de::{SeqAccess, Visitor},
+    de::{MapAccess, SeqAccess, Visitor},
     Deserialize,
 };
 
+#[cfg(feature = "alloc")]
+use crate::Skipped;
+use crate::{
+    elements::{self, Elements},
+    Drain, Options,
+};
+
 mod fold;
 pub use fold::*;
 
@@ -94,12 +168,77 @@ pub use for_each::*;
 mod find;
 pub use find::*;
 
+mod count;
+pub use count::*;
+
+mod any;
+pub use any::*;
+
+mod all;
+pub use all::*;
+
+mod position;
+pub use position::*;
+
+mod find_map;
+pub use find_map::*;
+
+mod last;
+pub use last::*;
+
+mod nth;
+pub use nth::*;
+
+mod min_by_key;
+pub use min_by_key::*;
+
+mod max_by_key;
+pub use max_by_key::*;
+
+mod sum;
+pub use sum::*;
+
+mod product;
+pub use product::*;
+
+mod reduce;
+pub use reduce::*;
+
+mod partition;
+pub use partition::*;
+
+mod fallible_fold;
+pub use fallible_fold::*;
+
+mod adapters;
+pub use adapters::*;
+
+mod tuple;
+
+mod instance;
+pub use instance::*;
+
+mod merge;
+pub use merge::*;
+
+/// Derive macro implementing the aggregator traits from plain functions.
+#[cfg(feature = "derive")]
+pub use serde_deser_iter_derive::{Aggregate, ColumnAggregate};
+
+mod seed;
+pub use seed::*;
+
+mod map;
+pub use map::*;
+
 /// The entry point for deep deserialization.
 ///
 /// Provided with the right aggregator, it will after
 /// serialization contain the aggregated value.
 pub struct StreamSeqDeser<I: Aggregator> {
     value: I::Value,
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    skipped: elements::Skipped,
 }
 
 impl<I: Aggregator> Deref for StreamSeqDeser<I> {
@@ -120,12 +259,110 @@ impl<I: Aggregator> StreamSeqDeser<I> {
     pub fn into_inner(self) -> I::Value {
         self.value
     }
+
+    /// The items skipped according to the [`OnItemError`](crate::OnItemError) policy of the
+    /// aggregator's options.
+    #[cfg(feature = "alloc")]
+    pub fn skipped(&self) -> &Skipped {
+        &self.skipped
+    }
+}
+
+/// Deserialize a sequence to the value aggregated by `I`.
+///
+/// This allows to keep the plain aggregated type for the field of a struct, instead of
+/// [`StreamSeqDeser`], by using `#[serde(deserialize_with = "serde_deser_iter::deep::aggregate::<Agg, _>")]`.
+/// Shorthands are provided for the most common wrappers, such as [`fold()`] for [`Fold`].
+pub fn aggregate<'de, I, D>(deserializer: D) -> Result<I::Value, D::Error>
+where
+    I: Aggregator,
+    I::Item: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    StreamSeqDeser::<I>::deserialize(deserializer).map(StreamSeqDeser::into_inner)
+}
+
+/// The visitor aggregating a container of items with `I`, whose value is the aggregated value.
+///
+/// [`StreamSeqDeser`] requests the container from the format deserializer according to
+/// the [`Shape`](crate::Shape) of the aggregator's options. This visitor allows to request it
+/// directly instead, for instance from a hand-written [`Deserialize`] implementation.
+///
+/// ```
+/// use serde::Deserializer;
+/// use serde_deser_iter::deep::{AggregateVisitor, Sum};
+///
+/// let mut json_deserializer = serde_json::Deserializer::from_str("[3, 1, 4]");
+/// let sum = json_deserializer
+///     .deserialize_tuple(3, AggregateVisitor::<Sum<u32>>::new())
+///     .unwrap();
+/// assert_eq!(sum, 8);
+/// ```
+pub struct AggregateVisitor<I> {
+    marker: PhantomData<I>,
+}
+
+impl<I> AggregateVisitor<I> {
+    /// Create the visitor.
+    pub fn new() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<I> Default for AggregateVisitor<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Aggregator> AggregateVisitor<I> {
+    #[allow(clippy::type_complexity)]
+    fn vis<'a>(
+    ) -> Vis<'a, I::Acc, I::Item, I::Break, fn(I::Acc, I::Item) -> ControlFlow<I::Break, I::Acc>>
+    {
+        let mut vis = Vis::new(I::init(), I::try_fold as fn(_, _) -> _, I::OPTIONS);
+        vis.error = I::error;
+        vis
+    }
+}
+
+impl<'de, I: Aggregator> Visitor<'de> for AggregateVisitor<I>
+where
+    I::Item: Deserialize<'de>,
+{
+    type Value = I::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        I::OPTIONS.shape.expecting(formatter)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        reject_abort(I::OPTIONS)?;
+        Self::vis().visit_seq(seq).map(I::finalize)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        reject_abort(I::OPTIONS)?;
+        Self::vis().visit_map(map).map(I::finalize)
+    }
 }
 
 /// The trait on which all agregation is based.
 ///
 /// User should often not implement this directly but rather rely on the
 /// provided [implementors](#implementors) and their associated traits.
+///
+/// The trait has no lifetime of its own: to aggregate items borrowing from the
+/// deserializer's input, the implementor takes the lifetime as a parameter
+/// (see [borrowed items](self#borrowed-items)).
 pub trait Aggregator {
     /// The accumulator type
     type Acc;
@@ -136,6 +373,9 @@ pub trait Aggregator {
     /// The final agregated type
     type Value;
 
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
     /// Initial value of the accumulator
     fn init() -> Self::Acc;
 
@@ -146,42 +386,120 @@ pub trait Aggregator {
     ///
     /// This can be identity if `Value = ControlFlow<Self::Break, Self::Acc>`
     fn finalize(x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value;
+
+    /// The hard error of the aggregation state, if any.
+    ///
+    /// It is checked after each item, and a state with an error fails the whole deserialization
+    /// with it, through [`serde::de::Error::custom`]. Such a state is never finalized.
+    /// Aggregators returning `Result`s are provided by [`FallibleFold`].
+    fn error<'s>(
+        state: ControlFlow<&'s Self::Break, &'s Self::Acc>,
+    ) -> Option<&'s dyn fmt::Display> {
+        let _ = state;
+        None
+    }
 }
 
-struct Vis<I> {
-    marker: PhantomData<I>,
+/// The [`Aggregator::error`] function of an aggregator.
+pub(crate) type ErrorFn<Break, Acc> =
+    for<'s> fn(ControlFlow<&'s Break, &'s Acc>) -> Option<&'s dyn fmt::Display>;
+
+/// The [`ErrorFn`] of aggregators which never fail.
+pub(crate) fn no_error<'s, Break, Acc>(
+    _: ControlFlow<&'s Break, &'s Acc>,
+) -> Option<&'s dyn fmt::Display> {
+    None
 }
 
-impl<'de, I: Aggregator> Visitor<'de> for Vis<I>
+/// Reject the [`Drain::Abort`] strategy of `options`, whose early return value can only be
+/// recovered through [`StreamSeqSeed::abort_on_break`]: any early return would otherwise fail
+/// the whole deserialization.
+fn reject_abort<E: serde::de::Error>(options: Options) -> Result<(), E> {
+    if options.drain == Drain::Abort {
+        return Err(E::custom(
+            "Drain::Abort is not supported in the options of an aggregator, \
+             use StreamSeqSeed::abort_on_break instead",
+        ));
+    }
+    Ok(())
+}
+
+/// Borrow the content of an aggregation state.
+pub(crate) fn as_ref<B, C>(state: &ControlFlow<B, C>) -> ControlFlow<&B, &C> {
+    match state {
+        ControlFlow::Continue(acc) => ControlFlow::Continue(acc),
+        ControlFlow::Break(clot_break) => ControlFlow::Break(clot_break),
+    }
+}
+
+struct Vis<'a, Acc, Item, Break, F> {
+    #[allow(clippy::type_complexity)]
+    marker: PhantomData<fn(Acc, Item) -> ControlFlow<Break, Acc>>,
+    init: Acc,
+    f: F,
+    error: ErrorFn<Break, Acc>,
+    options: Options,
+    aborted: Option<&'a mut Aborted<Break>>,
+    skipped: Option<&'a mut elements::Skipped>,
+}
+
+impl<'a, Acc, Item, Break, F> Vis<'a, Acc, Item, Break, F> {
+    fn new(init: Acc, f: F, options: Options) -> Self {
+        Self {
+            marker: PhantomData,
+            init,
+            f,
+            error: no_error,
+            options,
+            aborted: None,
+            skipped: None,
+        }
+    }
+}
+
+impl<'de, 'a, Acc, Item, Break, F> Visitor<'de> for Vis<'a, Acc, Item, Break, F>
 where
-    I::Item: Deserialize<'de>,
+    F: FnMut(Acc, Item) -> ControlFlow<Break, Acc>,
+    Item: Deserialize<'de>,
 {
-    type Value = I::Value;
+    type Value = ControlFlow<Break, Acc>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("a sequence")
+        self.options.shape.expecting(formatter)
     }
 
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
     where
         A: SeqAccess<'de>,
     {
-        let mut acc = I::init();
-        let fin;
-        'outer: {
-            while let Some(value) = seq.next_element()? {
-                match I::try_fold(acc, value) {
-                    ControlFlow::Continue(new_acc) => acc = new_acc,
-                    ControlFlow::Break(clot_break) => {
-                        while seq.next_element::<I::Item>()?.is_some() {}
-                        fin = ControlFlow::Break(clot_break);
-                        break 'outer;
+        let mut acc = self.init;
+        let mut elements = Elements::new(self.options, self.skipped);
+        while let Some(value) = elements.next(&mut seq)? {
+            let state = (self.f)(acc, value);
+            if let Some(e) = (self.error)(as_ref(&state)) {
+                return Err(elements.error(e));
+            }
+            match state {
+                ControlFlow::Continue(new_acc) => acc = new_acc,
+                ControlFlow::Break(clot_break) => {
+                    if let (Drain::Abort, Some(aborted)) = (self.options.drain, self.aborted) {
+                        aborted.0 = Some(clot_break);
+                        return Err(Drain::abort_error());
                     }
+                    self.options.drain.drain::<Item, _>(&mut seq)?;
+                    return Ok(ControlFlow::Break(clot_break));
                 }
             }
-            fin = ControlFlow::Continue(acc)
         }
-        Ok(I::finalize(fin))
+        Ok(ControlFlow::Continue(acc))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let shape = self.options.shape;
+        elements::visit_map_values(self, shape, map)
     }
 }
 
@@ -193,10 +511,15 @@ where
     where
         D: serde::Deserializer<'de>,
     {
-        let vis = Vis::<I> {
-            marker: PhantomData,
-        };
-        let fin = deserializer.deserialize_seq(vis)?;
-        Ok(Self { value: fin })
+        reject_abort(I::OPTIONS)?;
+        let mut skipped = elements::Skipped::new();
+        let mut vis = Vis::new(I::init(), I::try_fold, I::OPTIONS);
+        vis.error = I::error;
+        vis.skipped = Some(&mut skipped);
+        let fin = I::OPTIONS.shape.deserialize(deserializer, vis)?;
+        Ok(Self {
+            value: I::finalize(fin),
+            skipped,
+        })
     }
 }
@@ -0,0 +1,37 @@
+use core::{marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+
+/// A wrapper returning the `N`-th item (starting from 0) of type `T`
+pub struct Nth<T, const N: usize> {
+    marker: PhantomData<T>,
+}
+
+impl<T, const N: usize> Aggregator for Nth<T, N> {
+    type Acc = usize;
+
+    type Item = T;
+
+    type Break = T;
+
+    type Value = Option<T>;
+
+    fn init() -> Self::Acc {
+        N
+    }
+
+    fn try_fold(n: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        if n == 0 {
+            ControlFlow::Break(item)
+        } else {
+            ControlFlow::Continue(n - 1)
+        }
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(_) => None,
+            ControlFlow::Break(item) => Some(item),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use core::{convert::Infallible, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// A wrapper splitting the items in two collections, depending on whether they match a predicate
+pub struct Partition<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for partitioning aggregation
+pub trait PartitionAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+    /// The collections the items are put in
+    type Collection: Default + Extend<Self::Item>;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Core predicate, matching items go in the first collection
+    fn f(item: &Self::Item) -> bool;
+}
+
+impl<I> Aggregator for Partition<I>
+where
+    I: PartitionAggregator,
+{
+    type Acc = (I::Collection, I::Collection);
+
+    type Item = I::Item;
+
+    type Break = Infallible;
+
+    type Value = (I::Collection, I::Collection);
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    fn init() -> Self::Acc {
+        Default::default()
+    }
+
+    fn try_fold(
+        (mut matching, mut others): Self::Acc,
+        item: Self::Item,
+    ) -> ControlFlow<Self::Break, Self::Acc> {
+        if I::f(&item) {
+            matching.extend(Some(item));
+        } else {
+            others.extend(Some(item));
+        }
+        ControlFlow::Continue((matching, others))
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(acc) => acc,
+            ControlFlow::Break(_) => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use core::{marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// A wrapper searching for the position of the first item matching a predicate
+pub struct Position<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for searching the position of an item
+pub trait PositionAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Core predicate
+    fn f(item: Self::Item) -> bool;
+}
+
+impl<I> Aggregator for Position<I>
+where
+    I: PositionAggregator,
+{
+    type Acc = usize;
+
+    type Item = I::Item;
+
+    type Break = usize;
+
+    type Value = Option<usize>;
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    fn init() -> Self::Acc {
+        0
+    }
+
+    fn try_fold(idx: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        if I::f(item) {
+            ControlFlow::Break(idx)
+        } else {
+            ControlFlow::Continue(idx + 1)
+        }
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(_) => None,
+            ControlFlow::Break(idx) => Some(idx),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use core::{convert::Infallible, iter, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+
+/// A wrapper multiplying the items of type `T`
+pub struct Product<T> {
+    marker: PhantomData<T>,
+}
+
+impl<T> Aggregator for Product<T>
+where
+    T: iter::Product,
+{
+    type Acc = T;
+
+    type Item = T;
+
+    type Break = Infallible;
+
+    type Value = T;
+
+    fn init() -> Self::Acc {
+        iter::empty().product()
+    }
+
+    fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        ControlFlow::Continue([acc, item].into_iter().product())
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(acc) => acc,
+            ControlFlow::Break(_) => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use core::{convert::Infallible, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+use crate::Options;
+
+/// A wrapper reducing the items to a single one
+pub struct Reduce<I> {
+    marker: PhantomData<I>,
+}
+
+/// Functions for reducing aggregation
+pub trait ReduceAggregator {
+    /// The Item deserialized from the sequences
+    type Item;
+
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
+    /// Core reducing function
+    fn f(acc: Self::Item, item: Self::Item) -> Self::Item;
+}
+
+impl<I> Aggregator for Reduce<I>
+where
+    I: ReduceAggregator,
+{
+    type Acc = Option<I::Item>;
+
+    type Item = I::Item;
+
+    type Break = Infallible;
+
+    type Value = Option<I::Item>;
+
+    const OPTIONS: Options = I::OPTIONS;
+
+    fn init() -> Self::Acc {
+        None
+    }
+
+    fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        match acc {
+            None => ControlFlow::Continue(Some(item)),
+            Some(acc) => ControlFlow::Continue(Some(I::f(acc, item))),
+        }
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(acc) => acc,
+            ControlFlow::Break(_) => unreachable!(),
+        }
+    }
+}
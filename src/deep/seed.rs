@@ -0,0 +1,344 @@
+use core::{fmt, ops::ControlFlow};
+
+use serde::{
+    de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use super::Vis;
+#[cfg(feature = "alloc")]
+use crate::Skipped;
+use crate::{Drain, Options};
+
+/// The entry point for deep deserialization with a runtime closure.
+///
+/// Contrary to [`StreamSeqDeser`](super::StreamSeqDeser), which is driven by the static
+/// functions of an [`Aggregator`](super::Aggregator), this type holds an initial accumulator
+/// and a closure, and can therefore capture values from its environment.
+/// It implements [`DeserializeSeed`] and its value is the result of the
+/// fallible/early-returning fold of the sequence items.
+///
+/// To reach a sequence nested in a parent structure, see [`AtField`].
+///
+/// ```
+/// use core::{convert::Infallible, ops::ControlFlow};
+/// use serde::de::DeserializeSeed;
+/// use serde_deser_iter::deep::{AtField, StreamSeqSeed};
+///
+/// let threshold = 2;
+/// let mut json_deserializer =
+///     serde_json::Deserializer::from_str(r#"{"api_version": "x.y.z", "result": [1, 5, 3]}"#);
+/// let seed = StreamSeqSeed::new(0, |count, item: u32| {
+///     ControlFlow::<Infallible, _>::Continue(if item > threshold { count + 1 } else { count })
+/// });
+/// let above_threshold = AtField::new("result", seed)
+///     .deserialize(&mut json_deserializer)
+///     .unwrap();
+/// assert_eq!(above_threshold, ControlFlow::Continue(2));
+/// ```
+pub struct StreamSeqSeed<'a, Item, Acc, Break, F> {
+    vis: Vis<'a, Acc, Item, Break, F>,
+}
+
+impl<'a, Item, Acc, Break, F> StreamSeqSeed<'a, Item, Acc, Break, F>
+where
+    F: FnMut(Acc, Item) -> ControlFlow<Break, Acc>,
+{
+    /// Create a seed aggregating the sequence items using `f`, starting from `init`.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    pub fn new(init: Acc, f: F) -> Self {
+        Self {
+            vis: Vis::new(init, f, Options::new()),
+        }
+    }
+
+    /// Use `options` to process the sequence.
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.vis.options = options;
+        self
+    }
+
+    /// Abort the deserialization of the whole input on early return, see [`Drain::Abort`].
+    ///
+    /// The early return value is stored in `aborted`, which allows to recover it from
+    /// the result of the whole deserialization with [`Aborted::recover`].
+    ///
+    /// ```
+    /// use core::ops::ControlFlow;
+    /// use serde::de::DeserializeSeed;
+    /// use serde_deser_iter::deep::{Aborted, AtField, StreamSeqSeed};
+    ///
+    /// // The input is truncated after the item we are looking for.
+    /// let mut json_deserializer =
+    ///     serde_json::Deserializer::from_str(r#"{"result": [1, 5, 3, "#);
+    /// let mut aborted = Aborted::new();
+    /// let seed = StreamSeqSeed::new((), |(), item: u32| {
+    ///     if item > 2 {
+    ///         ControlFlow::Break(item)
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// });
+    /// let res = AtField::new("result", seed.abort_on_break(&mut aborted))
+    ///     .deserialize(&mut json_deserializer);
+    /// assert_eq!(aborted.recover(res).unwrap(), ControlFlow::Break(5));
+    /// ```
+    pub fn abort_on_break(mut self, aborted: &'a mut Aborted<Break>) -> Self {
+        self.vis.options = self.vis.options.drain(Drain::Abort);
+        self.vis.aborted = Some(aborted);
+        self
+    }
+
+    /// Report the items skipped according to the [`OnItemError`](crate::OnItemError) policy
+    /// of the options in `skipped`.
+    #[cfg(feature = "alloc")]
+    pub fn report_skipped(mut self, skipped: &'a mut Skipped) -> Self {
+        self.vis.skipped = Some(skipped);
+        self
+    }
+}
+
+impl<'de, 'a, Item, Acc, Break, F> DeserializeSeed<'de> for StreamSeqSeed<'a, Item, Acc, Break, F>
+where
+    F: FnMut(Acc, Item) -> ControlFlow<Break, Acc>,
+    Item: Deserialize<'de>,
+{
+    type Value = ControlFlow<Break, Acc>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.vis.options.shape.deserialize(deserializer, self.vis)
+    }
+}
+
+/// The seed is also the visitor of the container, which allows to request it from
+/// the format deserializer with another shape than the one of its options.
+impl<'de, 'a, Item, Acc, Break, F> Visitor<'de> for StreamSeqSeed<'a, Item, Acc, Break, F>
+where
+    F: FnMut(Acc, Item) -> ControlFlow<Break, Acc>,
+    Item: Deserialize<'de>,
+{
+    type Value = ControlFlow<Break, Acc>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.vis.expecting(formatter)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.vis.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.vis.visit_map(map)
+    }
+}
+
+/// Storage for the early return value of an aborted deserialization.
+///
+/// See [`StreamSeqSeed::abort_on_break`].
+#[derive(Debug)]
+pub struct Aborted<B>(pub(super) Option<B>);
+
+impl<B> Aborted<B> {
+    /// Create an empty storage.
+    pub fn new() -> Self {
+        Self(None)
+    }
+
+    /// Translate the error raised to abort the deserialization back to the early return value.
+    ///
+    /// `res` is the result of the whole deserialization, which may have been
+    /// aborted by the seed this storage was given to.
+    pub fn recover<Acc, E>(
+        self,
+        res: Result<ControlFlow<B, Acc>, E>,
+    ) -> Result<ControlFlow<B, Acc>, E> {
+        match self.0 {
+            Some(clot_break) => Ok(ControlFlow::Break(clot_break)),
+            None => res,
+        }
+    }
+}
+
+impl<B> Default for Aborted<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A seed navigating to a field of a map (or a struct in self-describing formats).
+///
+/// The value of the field named `field` is deserialized using the inner seed,
+/// all other values are skipped. An error is raised if the field is missing.
+pub struct AtField<'f, S> {
+    field: &'f str,
+    seed: S,
+}
+
+impl<'f, S> AtField<'f, S> {
+    /// Deserialize the value of `field` using `seed`.
+    pub fn new(field: &'f str, seed: S) -> Self {
+        Self { field, seed }
+    }
+}
+
+impl<'de, 'f, S> DeserializeSeed<'de> for AtField<'f, S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'f, S> Visitor<'de> for AtField<'f, S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a map with a field `{}`", self.field)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut seed = Some(self.seed);
+        let mut res = None;
+        while let Some(is_field) = map.next_key_seed(KeyIs(self.field))? {
+            match seed.take() {
+                Some(seed) if is_field => res = Some(map.next_value_seed(seed)?),
+                s => {
+                    seed = s;
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        res.ok_or_else(|| serde::de::Error::custom(format_args!("missing field `{}`", self.field)))
+    }
+}
+
+/// A seed navigating to an element of a sequence.
+///
+/// The element at position `index` (starting from 0) is deserialized using the inner seed,
+/// all other elements are skipped. An error is raised if the sequence is too short.
+pub struct AtIndex<S> {
+    index: usize,
+    seed: S,
+}
+
+impl<S> AtIndex<S> {
+    /// Deserialize the element at `index` using `seed`.
+    pub fn new(index: usize, seed: S) -> Self {
+        Self { index, seed }
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for AtIndex<S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, S> Visitor<'de> for AtIndex<S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of more than {} elements", self.index)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let expected = ExpectedLen(self.index + 1);
+        for skipped in 0..self.index {
+            if seq.next_element::<IgnoredAny>()?.is_none() {
+                return Err(serde::de::Error::invalid_length(skipped, &expected));
+            }
+        }
+        let Some(res) = seq.next_element_seed(self.seed)? else {
+            return Err(serde::de::Error::invalid_length(self.index, &expected));
+        };
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(res)
+    }
+}
+
+struct ExpectedLen(usize);
+
+impl serde::de::Expected for ExpectedLen {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of at least {} elements", self.0)
+    }
+}
+
+/// Seed deserializing a key and comparing it to the expected one.
+struct KeyIs<'f>(&'f str);
+
+impl<'de, 'f> DeserializeSeed<'de> for KeyIs<'f> {
+    type Value = bool;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de, 'f> Visitor<'de> for KeyIs<'f> {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a field name")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v == self.0)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v == self.0.as_bytes())
+    }
+
+    fn visit_u64<E>(self, _v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(false)
+    }
+}
@@ -0,0 +1,36 @@
+use core::{convert::Infallible, iter, marker::PhantomData, ops::ControlFlow};
+
+use super::Aggregator;
+
+/// A wrapper summing the items of type `T`
+pub struct Sum<T> {
+    marker: PhantomData<T>,
+}
+
+impl<T> Aggregator for Sum<T>
+where
+    T: iter::Sum,
+{
+    type Acc = T;
+
+    type Item = T;
+
+    type Break = Infallible;
+
+    type Value = T;
+
+    fn init() -> Self::Acc {
+        iter::empty().sum()
+    }
+
+    fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+        ControlFlow::Continue([acc, item].into_iter().sum())
+    }
+
+    fn finalize(f: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+        match f {
+            ControlFlow::Continue(acc) => acc,
+            ControlFlow::Break(_) => unreachable!(),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use core::{marker::PhantomData, ops::ControlFlow};
 
-use super::Aggregator;
+use serde::{Deserialize, Deserializer};
+
+use super::{Aggregator, StreamSeqDeser};
+use crate::Options;
 
 /// A wrapper for a fallible/early-returning folding aggregator
 pub struct TryFold<I> {
@@ -16,6 +19,9 @@ pub trait TryFoldAggregator {
     /// The early return type
     type Break;
 
+    /// The options used to process the sequence
+    const OPTIONS: Options = Options::new();
+
     /// Initial value of the accumulator
     fn init() -> Self::Acc;
 
@@ -35,6 +41,8 @@ where
 
     type Value = ControlFlow<I::Break, I::Acc>;
 
+    const OPTIONS: Options = I::OPTIONS;
+
     fn init() -> Self::Acc {
         I::init()
     }
@@ -47,3 +55,16 @@ where
         x
     }
 }
+
+/// Deserialize a sequence to the value folded by `I`, which may return early.
+///
+/// Meant to be used with `#[serde(deserialize_with = "serde_deser_iter::deep::try_fold::<Imp, _>")]`,
+/// see [`aggregate`](super::aggregate).
+pub fn try_fold<'de, I, D>(deserializer: D) -> Result<ControlFlow<I::Break, I::Acc>, D::Error>
+where
+    I: TryFoldAggregator,
+    I::Item: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    StreamSeqDeser::<TryFold<I>>::deserialize(deserializer).map(StreamSeqDeser::into_inner)
+}
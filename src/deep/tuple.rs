@@ -0,0 +1,88 @@
+use core::{fmt, ops::ControlFlow};
+
+use super::{as_ref, Aggregator};
+
+/// Feed an item to an aggregator which has not returned early yet.
+fn feed<A, F>(state: ControlFlow<A::Break, A::Acc>, item: F) -> ControlFlow<A::Break, A::Acc>
+where
+    A: Aggregator,
+    F: FnOnce() -> A::Item,
+{
+    match state {
+        ControlFlow::Continue(acc) => A::try_fold(acc, item()),
+        ControlFlow::Break(clot_break) => ControlFlow::Break(clot_break),
+    }
+}
+
+/// Run several aggregators on the same items in a single pass.
+///
+/// Each item is cloned for each member which has not returned early yet.
+/// The tuple only returns early once all its members have, but fails as soon as one
+/// of them does.
+macro_rules! tuple_aggregator {
+    ($($A:ident $a:ident),+; $L:ident $l:ident) => {
+        impl<Item, $($A,)+ $L> Aggregator for ($($A,)+ $L,)
+        where
+            Item: Clone,
+            $($A: Aggregator<Item = Item>,)+
+            $L: Aggregator<Item = Item>,
+        {
+            type Acc = (
+                $(ControlFlow<$A::Break, $A::Acc>,)+
+                ControlFlow<$L::Break, $L::Acc>,
+            );
+
+            type Item = Item;
+
+            type Break = ($($A::Break,)+ $L::Break,);
+
+            type Value = ($($A::Value,)+ $L::Value,);
+
+            fn init() -> Self::Acc {
+                (
+                    $(ControlFlow::Continue($A::init()),)+
+                    ControlFlow::Continue($L::init()),
+                )
+            }
+
+            fn try_fold(acc: Self::Acc, item: Self::Item) -> ControlFlow<Self::Break, Self::Acc> {
+                let ($($a,)+ $l,) = acc;
+                $(let $a = feed::<$A, _>($a, || item.clone());)+
+                let $l = feed::<$L, _>($l, || item);
+                match ($($a,)+ $l,) {
+                    ($(ControlFlow::Break($a),)+ ControlFlow::Break($l),) => {
+                        ControlFlow::Break(($($a,)+ $l,))
+                    }
+                    acc => ControlFlow::Continue(acc),
+                }
+            }
+
+            fn finalize(x: ControlFlow<Self::Break, Self::Acc>) -> Self::Value {
+                match x {
+                    ControlFlow::Continue(($($a,)+ $l,)) => ($($A::finalize($a),)+ $L::finalize($l),),
+                    ControlFlow::Break(($($a,)+ $l,)) => (
+                        $($A::finalize(ControlFlow::Break($a)),)+
+                        $L::finalize(ControlFlow::Break($l)),
+                    ),
+                }
+            }
+
+            fn error<'s>(state: ControlFlow<&'s Self::Break, &'s Self::Acc>) -> Option<&'s dyn fmt::Display> {
+                match state {
+                    ControlFlow::Continue(($($a,)+ $l,)) => None
+                        $(.or_else(|| $A::error(as_ref($a))))+
+                        .or_else(|| $L::error(as_ref($l))),
+                    ControlFlow::Break(($($a,)+ $l,)) => None
+                        $(.or_else(|| $A::error(ControlFlow::Break($a))))+
+                        .or_else(|| $L::error(ControlFlow::Break($l))),
+                }
+            }
+        }
+    };
+}
+
+tuple_aggregator!(A a; B b);
+tuple_aggregator!(A a, B b; C c);
+tuple_aggregator!(A a, B b, C c; D d);
+tuple_aggregator!(A a, B b, C c, D d; E e);
+tuple_aggregator!(A a, B b, C c, D d, E e; F f);
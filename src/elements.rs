@@ -0,0 +1,288 @@
+//! Reading the elements of the processed sequences, according to the [`Options`].
+
+use core::{fmt, marker::PhantomData};
+
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, Error, IgnoredAny, MapAccess, SeqAccess,
+    Unexpected, Visitor,
+};
+
+#[cfg(feature = "alloc")]
+use crate::content::Buffered;
+#[cfg(feature = "alloc")]
+pub(crate) use crate::skip::Skipped;
+use crate::{context, OnItemError, Options, Shape};
+
+/// Stand-in for the report of skipped items, which is never filled without `alloc`.
+#[cfg(not(feature = "alloc"))]
+pub struct Skipped;
+
+#[cfg(not(feature = "alloc"))]
+impl Skipped {
+    pub(crate) const fn new() -> Self {
+        Skipped
+    }
+}
+
+/// Where the items skipped by a [`top_level`](crate::top_level) aggregation are reported.
+pub trait SkippedSink {
+    fn into_skipped<'s>(self) -> Option<&'s mut Skipped>
+    where
+        Self: 's;
+}
+
+impl SkippedSink for () {
+    fn into_skipped<'s>(self) -> Option<&'s mut Skipped> {
+        None
+    }
+}
+
+impl SkippedSink for &mut Skipped {
+    fn into_skipped<'s>(self) -> Option<&'s mut Skipped>
+    where
+        Self: 's,
+    {
+        Some(self)
+    }
+}
+
+/// A seed which can be used for every element of a sequence.
+pub(crate) trait ElementSeed<'de> {
+    type Value;
+
+    fn deserialize<D>(&mut self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+impl<'de, T> ElementSeed<'de> for PhantomData<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn deserialize<D>(&mut self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+struct Reseed<'s, S>(&'s mut S);
+
+impl<'de, 's, S> DeserializeSeed<'de> for Reseed<'s, S>
+where
+    S: ElementSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.deserialize(deserializer)
+    }
+}
+
+struct Contextual<S> {
+    seed: S,
+    options: Options,
+    index: usize,
+}
+
+impl<'de, S> DeserializeSeed<'de> for Contextual<S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        context::deserialize(self.seed, deserializer, self.options, self.index)
+    }
+}
+
+/// Reader of the elements of a sequence, keeping track of their index.
+pub(crate) struct Elements<'s> {
+    options: Options,
+    index: usize,
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    skipped: Option<&'s mut Skipped>,
+}
+
+impl<'s> Elements<'s> {
+    pub(crate) fn new(options: Options, skipped: Option<&'s mut Skipped>) -> Self {
+        Self {
+            options,
+            index: 0,
+            skipped,
+        }
+    }
+
+    pub(crate) fn options(&self) -> Options {
+        self.options
+    }
+
+    /// An error failing the sequence after the last element read, located at its index
+    /// if the options ask for context.
+    pub(crate) fn error<E: Error>(&self, msg: &dyn fmt::Display) -> E {
+        match self.index.checked_sub(1) {
+            Some(index) if self.options.context => E::custom(format_args!("[{index}]: {msg}")),
+            _ => E::custom(msg),
+        }
+    }
+
+    /// Deserialize the next element of `seq` to an `Item`.
+    pub(crate) fn next<'de, Item, A>(&mut self, seq: &mut A) -> Result<Option<Item>, A::Error>
+    where
+        Item: Deserialize<'de>,
+        A: SeqAccess<'de>,
+    {
+        self.next_seed(seq, &mut PhantomData)
+    }
+
+    /// Deserialize the next element of `seq` with `seed`.
+    ///
+    /// Elements which cannot be deserialized are skipped if the options say so.
+    pub(crate) fn next_seed<'de, S, A>(
+        &mut self,
+        seq: &mut A,
+        seed: &mut S,
+    ) -> Result<Option<S::Value>, A::Error>
+    where
+        S: ElementSeed<'de>,
+        A: SeqAccess<'de>,
+    {
+        match self.options.on_item_error {
+            OnItemError::Abort => {
+                let item = seq.next_element_seed(Contextual {
+                    seed: Reseed(seed),
+                    options: self.options,
+                    index: self.index,
+                })?;
+                self.index += 1;
+                Ok(item)
+            }
+            #[cfg(feature = "alloc")]
+            policy => loop {
+                let content = match seq.next_element::<Buffered>()? {
+                    Some(content) => content,
+                    None => return Ok(None),
+                };
+                let index = self.index;
+                self.index += 1;
+                let deserializer = content.into_deserializer::<A::Error>();
+                match context::deserialize(Reseed(seed), deserializer, self.options, index) {
+                    Ok(item) => return Ok(Some(item)),
+                    Err(e) => {
+                        if let Some(skipped) = self.skipped.as_deref_mut() {
+                            skipped.record(policy, index, &e);
+                        }
+                    }
+                }
+            },
+        }
+    }
+
+    /// Deserialize the next entry of `map` to a `(K, V)` pair.
+    ///
+    /// Entries are located by their index as the elements of a sequence, and the entries
+    /// which cannot be deserialized are skipped if the options say so.
+    pub(crate) fn next_entry<'de, K, V, A>(
+        &mut self,
+        map: &mut A,
+    ) -> Result<Option<(K, V)>, A::Error>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        A: MapAccess<'de>,
+    {
+        match self.options.on_item_error {
+            OnItemError::Abort => {
+                let key = map.next_key_seed(Contextual {
+                    seed: PhantomData::<K>,
+                    options: self.options,
+                    index: self.index,
+                })?;
+                let key = match key {
+                    Some(key) => key,
+                    None => return Ok(None),
+                };
+                let value = map.next_value_seed(Contextual {
+                    seed: PhantomData::<V>,
+                    options: self.options,
+                    index: self.index,
+                })?;
+                self.index += 1;
+                Ok(Some((key, value)))
+            }
+            #[cfg(feature = "alloc")]
+            policy => loop {
+                let (key, value) = match map.next_entry::<Buffered, Buffered>()? {
+                    Some(entry) => entry,
+                    None => return Ok(None),
+                };
+                let index = self.index;
+                self.index += 1;
+                let key = key.into_key_deserializer::<A::Error>();
+                let value = value.into_deserializer::<A::Error>();
+                let entry = context::deserialize(PhantomData::<K>, key, self.options, index)
+                    .and_then(|key| {
+                        context::deserialize(PhantomData::<V>, value, self.options, index)
+                            .map(|value| (key, value))
+                    });
+                match entry {
+                    Ok(entry) => return Ok(Some(entry)),
+                    Err(e) => {
+                        if let Some(skipped) = self.skipped.as_deref_mut() {
+                            skipped.record(policy, index, &e);
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// The values of a map, read as a sequence.
+struct MapValues<A>(A);
+
+impl<'de, A> SeqAccess<'de> for MapValues<A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.0.next_key::<IgnoredAny>()? {
+            Some(IgnoredAny) => self.0.next_value_seed(seed).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+/// Visit the values of `map` as a sequence, if the `shape` of the container allows it.
+pub(crate) fn visit_map_values<'de, V, A>(
+    visitor: V,
+    shape: Shape,
+    map: A,
+) -> Result<V::Value, A::Error>
+where
+    V: Visitor<'de>,
+    A: MapAccess<'de>,
+{
+    match shape {
+        Shape::Any | Shape::MapValues => visitor.visit_seq(MapValues(map)),
+        Shape::Seq | Shape::Tuple(_) => Err(A::Error::invalid_type(Unexpected::Map, &visitor)),
+    }
+}
@@ -54,8 +54,8 @@
 //! ## Top-level
 //!
 //! The [`top_level`] module offers the most user friendly and powerful way to
-//! deserialize sequences. However, it is restricted to sequences defined at
-//! the top-level. For example it can work on each `{"name": ...}` from the following JSON
+//! deserialize sequences. However, it is restricted to a single sequence per
+//! data file, by default the top-level one. For example it can work on each `{"name": ...}` from the following JSON
 //!
 //! ```json
 //! [
@@ -65,7 +65,8 @@
 //! ]
 //! ```
 //!
-//! but not if they are deeper in the structure:
+//! and, by first descending along a path with [`AdaptersExt::at_path`](top_level::AdaptersExt::at_path),
+//! on those deeper in the structure, as long as only one sequence is processed:
 //!
 //! ```json
 //! {
@@ -80,16 +81,31 @@
 //! ## Deep
 //!
 //! The [`deep`] module allows working on sequences located at any depth
-//! (and even nested one, though cumbersomely). However it does not allow to
-//! run closures on the iterated items, only functions, and its interface is
-//! less intuitive than [`top_level`].
+//! (and even nested one, though cumbersomely). Its main entry point only runs
+//! functions on the iterated items, and its interface is less intuitive than
+//! [`top_level`]. Closures can still be used through [`deep::StreamSeqSeed`],
+//! at the cost of driving the deserialization by hand.
 //!
 //! # Early returns
 //!
 //! **Caution.**  In case of an early return from the aggregating function,
-//! all remaining items will still be deserialized (but discarded immediately).
+//! all remaining items will still be read (and discarded immediately).
 //! This is because the format deserializers expect to have consume the whole
-//! sequence before continuing.
+//! sequence before continuing. By default they are skipped without being deserialized
+//! to the item type, see [`Drain`] to change this behavior, and in particular
+//! [`Drain::Abort`] to stop reading the input altogether.
+//!
+//! # Container shapes
+//!
+//! Items are read from a sequence by default. Fixed-size tuples (as needed by formats which
+//! are not self-describing, such as bincode), the values of maps, or whatever a self-describing
+//! format holds can be read instead, see [`Shape`].
+//!
+//! # Malformed items
+//!
+//! By default, an item which cannot be deserialized to the item type makes the whole
+//! aggregation fail. With the `alloc` feature, such items can instead be skipped and
+//! reported, see [`OnItemError`].
 //!
 //! # FAQ
 //!
@@ -106,6 +122,40 @@
 //! serialziation separated by whitespace needs to be done by the format deserializer.
 //! For JSON for example, use [serde_json::StreamDeserializer](https://docs.rs/serde_json/latest/serde_json/struct.StreamDeserializer.html).
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+mod content;
+
+mod context;
+
+mod elements;
+
+mod options;
+pub use options::*;
+
+mod merge;
+pub use merge::*;
+
+#[cfg(feature = "std")]
+mod par;
+
+#[cfg(feature = "alloc")]
+mod skip;
+#[cfg(feature = "alloc")]
+pub use skip::*;
+
 pub mod deep;
 
 pub mod top_level;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "alloc")]
+    pub use alloc::collections::BTreeSet;
+    pub use serde;
+}
@@ -0,0 +1,129 @@
+#[cfg(feature = "alloc")]
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// Values which can be combined into one, such as the accumulators of a fold run on
+/// several sources.
+///
+/// Numbers are added, collections are extended, and the values of maps sharing
+/// the same key are merged. When the values are ordered, `self` comes before `other`.
+/// As with [`Iterator::sum`], the addition of integers panics on overflow when debug
+/// assertions are enabled, and wraps around otherwise.
+///
+/// The accumulators of the [`deep`](crate::deep) aggregators are merged through
+/// [`MergeableAggregator`](crate::deep::MergeableAggregator) instead.
+pub trait Merge {
+    /// Combine `self` with `other`.
+    fn merge(self, other: Self) -> Self;
+}
+
+macro_rules! merge_numbers {
+    ($($t:ty),*) => {$(
+        impl Merge for $t {
+            fn merge(self, other: Self) -> Self {
+                self + other
+            }
+        }
+    )*};
+}
+
+merge_numbers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl Merge for () {
+    fn merge(self, (): Self) -> Self {}
+}
+
+impl<T: Merge> Merge for Option<T> {
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Some(a), Some(b)) => Some(a.merge(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+macro_rules! merge_tuple {
+    ($($T:ident $a:ident $b:ident),+) => {
+        impl<$($T: Merge),+> Merge for ($($T,)+) {
+            fn merge(self, other: Self) -> Self {
+                let ($($a,)+) = self;
+                let ($($b,)+) = other;
+                ($($a.merge($b),)+)
+            }
+        }
+    };
+}
+
+merge_tuple!(A a1 a2);
+merge_tuple!(A a1 a2, B b1 b2);
+merge_tuple!(A a1 a2, B b1 b2, C c1 c2);
+merge_tuple!(A a1 a2, B b1 b2, C c1 c2, D d1 d2);
+merge_tuple!(A a1 a2, B b1 b2, C c1 c2, D d1 d2, E e1 e2);
+merge_tuple!(A a1 a2, B b1 b2, C c1 c2, D d1 d2, E e1 e2, F f1 f2);
+
+#[cfg(feature = "alloc")]
+impl<T> Merge for Vec<T> {
+    fn merge(mut self, mut other: Self) -> Self {
+        self.append(&mut other);
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> Merge for BTreeSet<T> {
+    fn merge(mut self, mut other: Self) -> Self {
+        self.append(&mut other);
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Ord, V: Merge> Merge for BTreeMap<K, V> {
+    fn merge(mut self, other: Self) -> Self {
+        for (key, value) in other {
+            let value = match self.remove(&key) {
+                Some(previous) => previous.merge(value),
+                None => value,
+            };
+            self.insert(key, value);
+        }
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> Merge for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn merge(mut self, other: Self) -> Self {
+        self.extend(other);
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> Merge for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    V: Merge,
+    S: BuildHasher,
+{
+    fn merge(mut self, other: Self) -> Self {
+        for (key, value) in other {
+            let value = match self.remove(&key) {
+                Some(previous) => previous.merge(value),
+                None => value,
+            };
+            self.insert(key, value);
+        }
+        self
+    }
+}
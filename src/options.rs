@@ -0,0 +1,366 @@
+use core::fmt;
+
+use serde::{
+    de::{Error, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+/// How the remaining items of a sequence (or entries of a map) are consumed after an early return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Drain {
+    /// Skip the remaining items using [`IgnoredAny`], without checking their type.
+    #[default]
+    Ignore,
+    /// Deserialize the remaining items to the item type (and discard them immediately),
+    /// so that items which do not match the item type are still reported as errors.
+    Validate,
+    /// Do not read the remaining items at all and abort the deserialization of the
+    /// whole input instead.
+    ///
+    /// This is done by raising an error through the format deserializer, which is then
+    /// translated back to the early return value, so that it is not reported as a failure.
+    /// The rest of the input is never read (nor validated), and the format deserializer
+    /// must not be used afterwards.
+    ///
+    /// In the [`deep`](crate::deep) module, the early return value can only be recovered
+    /// through [`StreamSeqSeed::abort_on_break`](crate::deep::StreamSeqSeed::abort_on_break).
+    /// The deserialization of the other entry points, such as
+    /// [`StreamSeqDeser`](crate::deep::StreamSeqDeser), fails right away when the
+    /// [`OPTIONS`](crate::deep::Aggregator::OPTIONS) of their aggregator use this strategy:
+    ///
+    /// ```
+    /// use serde_deser_iter::{
+    ///     deep::{Fold, FoldAggregator, StreamSeqDeser},
+    ///     Drain, Options,
+    /// };
+    ///
+    /// struct Sum;
+    ///
+    /// impl FoldAggregator for Sum {
+    ///     type Item = u32;
+    ///     type Acc = u32;
+    ///
+    ///     const OPTIONS: Options = Options::new().drain(Drain::Abort);
+    ///
+    ///     fn init() -> u32 {
+    ///         0
+    ///     }
+    ///
+    ///     fn f(acc: u32, item: u32) -> u32 {
+    ///         acc + item
+    ///     }
+    /// }
+    ///
+    /// let error = serde_json::from_str::<StreamSeqDeser<Fold<Sum>>>("[1, 2]")
+    ///     .err()
+    ///     .unwrap();
+    /// assert!(error.to_string().contains("abort_on_break"));
+    /// ```
+    ///
+    /// In the [`top_level`](crate::top_level) module, the early return value is returned as usual:
+    ///
+    /// ```
+    /// use serde_deser_iter::{top_level::AdaptersExt, Drain, Options};
+    ///
+    /// // The input is truncated after the item we are looking for.
+    /// let mut json_deserializer = serde_json::Deserializer::from_str("[1, 2, 3, ");
+    /// let found = json_deserializer
+    ///     .with_options(Options::new().drain(Drain::Abort))
+    ///     .find(|item: &u32| *item == 2);
+    /// assert_eq!(found.unwrap(), Some(2));
+    /// ```
+    Abort,
+}
+
+impl Drain {
+    /// Consume all remaining items of `seq` according to this strategy.
+    pub(crate) fn drain<'de, Item, A>(self, seq: &mut A) -> Result<(), A::Error>
+    where
+        Item: Deserialize<'de>,
+        A: SeqAccess<'de>,
+    {
+        match self {
+            Drain::Ignore => while seq.next_element::<IgnoredAny>()?.is_some() {},
+            Drain::Validate => while seq.next_element::<Item>()?.is_some() {},
+            Drain::Abort => return Err(Self::abort_error()),
+        }
+        Ok(())
+    }
+
+    /// Consume all remaining entries of `map` according to this strategy.
+    pub(crate) fn drain_map<'de, K, V, A>(self, map: &mut A) -> Result<(), A::Error>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        A: MapAccess<'de>,
+    {
+        match self {
+            Drain::Ignore => while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {},
+            Drain::Validate => while map.next_entry::<K, V>()?.is_some() {},
+            Drain::Abort => return Err(Self::abort_error()),
+        }
+        Ok(())
+    }
+
+    /// The sentinel error raised through the format deserializer by [`Drain::Abort`].
+    pub(crate) fn abort_error<E: Error>() -> E {
+        E::custom("deserialization aborted after an early return")
+    }
+}
+
+/// What to do with an item which cannot be deserialized to the item type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnItemError {
+    /// Fail with the deserialization error.
+    #[default]
+    Abort,
+    /// Skip the item and go on with the next ones.
+    ///
+    /// To do so, each item is first buffered in a format-agnostic form and then converted to
+    /// the item type, which needs the format to be self-describing (as JSON is, but not
+    /// bincode), and allocates. Malformed input still fails, as the items after it cannot be
+    /// found anymore.
+    ///
+    /// The skipped items are counted in a [`Skipped`](crate::Skipped) report, obtained through
+    /// [`Configured::report_skipped`](crate::top_level::Configured::report_skipped)
+    /// in the [`top_level`](crate::top_level) module, and through
+    /// [`StreamSeqDeser::skipped`](crate::deep::StreamSeqDeser::skipped),
+    /// [`StreamMapDeser::skipped`](crate::deep::StreamMapDeser::skipped) or
+    /// [`StreamSeqSeed::report_skipped`](crate::deep::StreamSeqSeed::report_skipped)
+    /// in the [`deep`](crate::deep) module.
+    ///
+    /// ```
+    /// use serde_deser_iter::{top_level::AdaptersExt, OnItemError, Options, Skipped};
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"[1, "two", 3, -4]"#);
+    /// let mut skipped = Skipped::new();
+    /// let sum = json_deserializer
+    ///     .with_options(Options::new().on_item_error(OnItemError::Collect))
+    ///     .report_skipped(&mut skipped)
+    ///     .fold(0, |acc, item: u32| acc + item)
+    ///     .unwrap();
+    /// assert_eq!(sum, 4);
+    /// assert_eq!(skipped.count(), 2);
+    /// assert_eq!(skipped.items()[1].index(), 3);
+    /// ```
+    ///
+    /// Human-readable formats such as JSON write all map keys as strings and parse them back
+    /// to the requested type: the buffered keys of such formats are parsed back to numbers
+    /// and booleans as well, so that the same items are accepted with or without skipping.
+    /// The keys of other formats keep their type.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use serde_deser_iter::{top_level::AdaptersExt, OnItemError, Options};
+    ///
+    /// let mut json_deserializer =
+    ///     serde_json::Deserializer::from_str(r#"[{"1": 10}, {"2": "x"}, {"3": 30}]"#);
+    /// let sum = json_deserializer
+    ///     .with_options(Options::new().on_item_error(OnItemError::Collect))
+    ///     .fold(0, |acc, item: HashMap<u64, u32>| {
+    ///         acc + item.iter().map(|(k, v)| k * u64::from(*v)).sum::<u64>()
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(sum, 100);
+    /// ```
+    ///
+    /// Enums and strings borrowed from the input are buffered too:
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_deser_iter::{top_level::AdaptersExt, OnItemError, Options};
+    ///
+    /// #[derive(Deserialize)]
+    /// enum Shape {
+    ///     Circle(f64),
+    ///     Square { side: f64 },
+    ///     Empty,
+    /// }
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(
+    ///     r#"[{"Circle": 1.0}, {"Triangle": 2.0}, {"Square": {"side": 2.0}}, "Empty"]"#,
+    /// );
+    /// let shapes = json_deserializer
+    ///     .with_options(Options::new().on_item_error(OnItemError::Skip))
+    ///     .fold(Vec::new(), |mut acc, item: Shape| {
+    ///         acc.push(item);
+    ///         acc
+    ///     })
+    ///     .unwrap();
+    /// assert!(matches!(
+    ///     shapes[..],
+    ///     [Shape::Circle(_), Shape::Square { .. }, Shape::Empty]
+    /// ));
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"["a", 2, "b"]"#);
+    /// let words = json_deserializer
+    ///     .with_options(Options::new().on_item_error(OnItemError::Skip))
+    ///     .fold(Vec::new(), |mut acc, item: &str| {
+    ///         acc.push(item);
+    ///         acc
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(words, ["a", "b"]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    Skip,
+    /// Same as [`Skip`](OnItemError::Skip), also keeping the index of the skipped items and the
+    /// message of their error in the [`Skipped`](crate::Skipped) report.
+    #[cfg(feature = "alloc")]
+    Collect,
+}
+
+/// The shape of the container holding the items, i.e. how it is requested from the
+/// format deserializer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Shape {
+    /// A sequence of any length, through [`Deserializer::deserialize_seq`].
+    #[default]
+    Seq,
+    /// A tuple (or fixed-size array) of the given length, through
+    /// [`Deserializer::deserialize_tuple`].
+    ///
+    /// Formats which are not self-describing, such as bincode or postcard, do not store the
+    /// length of tuples and can only read them this way.
+    ///
+    /// ```
+    /// use bincode::Options as _;
+    /// use serde_deser_iter::{top_level::AdaptersExt, Options, Shape};
+    ///
+    /// let bytes = bincode::DefaultOptions::new().serialize(&[3u32, 1, 4]).unwrap();
+    /// let mut bincode_deserializer =
+    ///     bincode::Deserializer::from_slice(&bytes, bincode::DefaultOptions::new());
+    /// let sum = (&mut bincode_deserializer)
+    ///     .with_options(Options::new().shape(Shape::Tuple(3)))
+    ///     .sum::<u32, u32>()
+    ///     .unwrap();
+    /// assert_eq!(sum, 8);
+    /// ```
+    Tuple(usize),
+    /// Whatever the input holds, through [`Deserializer::deserialize_any`]: either a sequence,
+    /// or a map whose values are the items.
+    ///
+    /// Only self-describing formats support it, but they may report some containers,
+    /// such as sets, in a way only [`Deserializer::deserialize_any`] accepts.
+    Any,
+    /// The values of a map, through [`Deserializer::deserialize_map`], the keys being ignored.
+    ///
+    /// ```
+    /// use serde_deser_iter::{top_level::AdaptersExt, Options, Shape};
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"{"bob": 3, "alice": 5}"#);
+    /// let max = json_deserializer
+    ///     .with_options(Options::new().shape(Shape::MapValues))
+    ///     .fold(0, |max, item: u32| max.max(item))
+    ///     .unwrap();
+    /// assert_eq!(max, 5);
+    /// ```
+    MapValues,
+}
+
+impl Shape {
+    /// Request a container of this shape from `deserializer`.
+    pub(crate) fn deserialize<'de, D, V>(
+        self,
+        deserializer: D,
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Visitor<'de>,
+    {
+        match self {
+            Shape::Seq => deserializer.deserialize_seq(visitor),
+            Shape::Tuple(len) => deserializer.deserialize_tuple(len, visitor),
+            Shape::Any => deserializer.deserialize_any(visitor),
+            Shape::MapValues => deserializer.deserialize_map(visitor),
+        }
+    }
+
+    /// What a visitor of a container of this shape expects.
+    pub(crate) fn expecting(self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Shape::Seq => formatter.write_str("a sequence"),
+            Shape::Tuple(len) => write!(formatter, "a tuple of size {len}"),
+            Shape::Any => formatter.write_str("a sequence or a map"),
+            Shape::MapValues => formatter.write_str("a map"),
+        }
+    }
+}
+
+/// Options tuning how sequences are processed.
+///
+/// They are set for the [`top_level`](crate::top_level) module through
+/// [`AdaptersExt::with_options`](crate::top_level::AdaptersExt::with_options),
+/// and for the [`deep`](crate::deep) module through the `OPTIONS` associated constant of
+/// the aggregator traits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Options {
+    pub(crate) drain: Drain,
+    pub(crate) context: bool,
+    pub(crate) on_item_error: OnItemError,
+    pub(crate) shape: Shape,
+}
+
+impl Options {
+    /// The default options.
+    pub const fn new() -> Self {
+        Self {
+            drain: Drain::Ignore,
+            context: false,
+            on_item_error: OnItemError::Abort,
+            shape: Shape::Seq,
+        }
+    }
+
+    /// Set how the remaining items are consumed after an early return.
+    pub const fn drain(mut self, drain: Drain) -> Self {
+        self.drain = drain;
+        self
+    }
+
+    /// Attach the position of the failing value to deserialization errors.
+    ///
+    /// The errors raised while deserializing an item are prefixed with the zero-based index of
+    /// the item in the sequence, followed by the path of the failing value inside of it,
+    /// e.g. `[3].subscribed_to[2]`. The keys of the path are those seen by the item's
+    /// [`Deserialize`] implementation: formats which do not give the field names of structs
+    /// (such as bincode) only report the index of the fields. Keys which cannot be borrowed
+    /// from the input are truncated.
+    ///
+    /// This wraps the deserialization of every item, and is therefore disabled by default.
+    ///
+    /// ```
+    /// use serde_deser_iter::{top_level::AdaptersExt, Options};
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct DataEntry {
+    ///     subscribed_to: Vec<String>,
+    /// }
+    ///
+    /// let json = r#"[{"subscribed_to": ["rust"]}, {"subscribed_to": ["rust", 42]}]"#;
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(json);
+    /// let error = json_deserializer
+    ///     .with_options(Options::new().error_context(true))
+    ///     .for_each(|_: DataEntry| ())
+    ///     .unwrap_err();
+    /// assert!(error.to_string().starts_with("[1].subscribed_to[1]: invalid type"));
+    /// ```
+    pub const fn error_context(mut self, enabled: bool) -> Self {
+        self.context = enabled;
+        self
+    }
+
+    /// Set what to do with the items which cannot be deserialized to the item type.
+    pub const fn on_item_error(mut self, on_item_error: OnItemError) -> Self {
+        self.on_item_error = on_item_error;
+        self
+    }
+
+    /// Set the shape of the container holding the items.
+    pub const fn shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+}
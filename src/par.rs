@@ -0,0 +1,131 @@
+//! Folding of the items of a sequence on a pool of worker threads.
+
+use alloc::{string::String, string::ToString, vec::Vec};
+use core::{
+    convert::Infallible,
+    mem,
+    num::NonZeroUsize,
+    ops::ControlFlow,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use std::{
+    panic,
+    sync::{mpsc, Arc, Mutex, PoisonError},
+    thread,
+};
+
+use serde::de::Error;
+
+use crate::deep::ErrorFn;
+
+/// Number of items sent at once to a worker.
+const BATCH_SIZE: usize = 64;
+
+/// Fold the items pushed by `drive` on one worker per available core.
+///
+/// Each worker folds the batches of items it receives into its own accumulator, obtained
+/// from `init`, and the accumulators of all workers are combined with `merge` at the end.
+/// The accumulators are checked with `error` after each item and once merged, as the states
+/// of an [`Aggregator`](crate::deep::Aggregator).
+///
+/// `push` breaks once an accumulator has an error, or once all the workers have panicked,
+/// as they own the receiving end of the channel. `drive` should then stop reading the input,
+/// and the error or the panic is forwarded.
+pub(crate) fn par_fold<Item, Acc, E, I, F, M, D>(
+    mut init: I,
+    fold: F,
+    merge: M,
+    error: ErrorFn<Infallible, Acc>,
+    drive: D,
+) -> Result<Acc, E>
+where
+    Item: Send,
+    Acc: Send,
+    E: Error,
+    I: FnMut() -> Acc,
+    F: Fn(Acc, Item) -> Acc + Sync,
+    M: FnMut(Acc, Acc) -> Acc,
+    D: FnOnce(&mut dyn FnMut(Item) -> ControlFlow<()>) -> Result<(), E>,
+{
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let (sender, receiver) = mpsc::sync_channel::<Vec<Item>>(2 * workers);
+    let receiver = Arc::new(Mutex::new(receiver));
+    let failed = AtomicBool::new(false);
+    let failure = Mutex::new(None::<String>);
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let mut acc = init();
+                let (fold, receiver) = (&fold, Arc::clone(&receiver));
+                let (failed, failure) = (&failed, &failure);
+                scope.spawn(move || loop {
+                    // The lock is released before folding the batch.
+                    let batch = receiver
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .recv();
+                    let Ok(batch) = batch else {
+                        return Some(acc);
+                    };
+                    for item in batch {
+                        acc = fold(acc, item);
+                        if let Some(e) = error(ControlFlow::Continue(&acc)) {
+                            failure
+                                .lock()
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .get_or_insert_with(|| e.to_string());
+                            failed.store(true, Ordering::Relaxed);
+                            return None;
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(receiver);
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let res = drive(&mut |item| {
+            batch.push(item);
+            if batch.len() < BATCH_SIZE {
+                return ControlFlow::Continue(());
+            }
+            if failed.load(Ordering::Relaxed) {
+                return ControlFlow::Break(());
+            }
+            let full = mem::replace(&mut batch, Vec::with_capacity(BATCH_SIZE));
+            match sender.send(full) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(_) => ControlFlow::Break(()),
+            }
+        });
+        if res.is_ok() && !batch.is_empty() {
+            // The workers may all have panicked, which is reported below.
+            let _ = sender.send(batch);
+        }
+        drop(sender);
+        let accs: Vec<Option<Acc>> = handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .collect();
+        if let Some(e) = failure
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+        {
+            return Err(E::custom(e));
+        }
+        res?;
+        let acc = accs
+            .into_iter()
+            .flatten()
+            .reduce(merge)
+            .expect("there is at least one worker");
+        match error(ControlFlow::Continue(&acc)) {
+            Some(e) => Err(E::custom(e)),
+            None => Ok(acc),
+        }
+    })
+}
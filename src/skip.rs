@@ -0,0 +1,71 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use crate::OnItemError;
+
+/// The items skipped because they could not be deserialized to the item type.
+///
+/// It is filled when the [`OnItemError`] policy is [`Skip`](OnItemError::Skip) or
+/// [`Collect`](OnItemError::Collect), the reasons being only kept for the latter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Skipped {
+    count: usize,
+    items: Vec<SkippedItem>,
+}
+
+impl Skipped {
+    /// An empty report.
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            items: Vec::new(),
+        }
+    }
+
+    /// Number of items skipped.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The skipped items along with the reason they were skipped, if they were collected.
+    pub fn items(&self) -> &[SkippedItem] {
+        &self.items
+    }
+
+    /// Take ownership of the skipped items.
+    pub fn into_items(self) -> Vec<SkippedItem> {
+        self.items
+    }
+
+    pub(crate) fn record(&mut self, policy: OnItemError, index: usize, error: &dyn Display) {
+        self.count += 1;
+        if policy == OnItemError::Collect {
+            self.items.push(SkippedItem {
+                index,
+                message: error.to_string(),
+            });
+        }
+    }
+}
+
+/// An item which could not be deserialized to the item type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedItem {
+    index: usize,
+    message: String,
+}
+
+impl SkippedItem {
+    /// Zero-based index of the item in the sequence.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The message of the deserialization error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
@@ -0,0 +1,568 @@
+use core::{
+    fmt,
+    iter::{Product, Sum},
+    ops::ControlFlow,
+};
+
+use serde::{Deserialize, Deserializer};
+
+#[cfg(feature = "std")]
+use super::pipelined;
+use super::{
+    break_error, lift_infallible, try_fold_seq, AsPathSegment, AtPath, DeserInPlace,
+    DeserTryFolder, DeserWithIter, FoldTuple, InPlace, MapWrapper, TryFoldTuple, Wrapper,
+};
+#[cfg(feature = "std")]
+use crate::Drain;
+#[cfg(feature = "alloc")]
+use crate::Skipped;
+use crate::{
+    deep::{no_error, Aggregator},
+    elements::{Elements, SkippedSink},
+    Options,
+};
+
+/// A deserializer along with the [`Options`] used to process its sequence.
+///
+/// It is obtained through [`AdaptersExt::with_options`](super::AdaptersExt::with_options),
+/// and offers the same methods as [`DeserializerExt`](super::DeserializerExt), which use the options.
+pub struct Configured<D, S = ()> {
+    deserializer: D,
+    options: Options,
+    sink: S,
+}
+
+impl<D> Configured<D> {
+    /// Process the sequence of `deserializer` with the default options.
+    pub(super) fn wrap(deserializer: D) -> Self {
+        Self::new(deserializer, Options::new(), ())
+    }
+}
+
+impl<D, S> Configured<D, S> {
+    pub(super) fn new(deserializer: D, options: Options, sink: S) -> Self {
+        Self {
+            deserializer,
+            options,
+            sink,
+        }
+    }
+
+    /// The options in use.
+    pub fn options(&self) -> Options {
+        self.options
+    }
+
+    /// Use `options` instead of the current ones.
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Report the items skipped according to the [`OnItemError`](crate::OnItemError) policy
+    /// of the options in `skipped`.
+    ///
+    /// ```
+    /// use serde_deser_iter::{top_level::AdaptersExt, OnItemError, Options, Skipped};
+    ///
+    /// let json = r#"{"a": 1, "b": "x", "c": 3}"#;
+    /// let mut skipped = Skipped::new();
+    /// let sum = serde_json::Deserializer::from_str(json)
+    ///     .with_options(Options::new().on_item_error(OnItemError::Collect))
+    ///     .report_skipped(&mut skipped)
+    ///     .fold_entries(0, |acc, (_, value): (String, u32)| acc + value)
+    ///     .unwrap();
+    /// assert_eq!(sum, 4);
+    /// assert_eq!(skipped.items()[0].index(), 1);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn report_skipped(self, skipped: &mut Skipped) -> Configured<D, &mut Skipped> {
+        Configured::new(self.deserializer, self.options, skipped)
+    }
+
+    /// Same as [`AdaptersExt::at_path`](super::AdaptersExt::at_path), keeping the options.
+    pub fn at_path<'p, P>(self, path: &'p [P]) -> Configured<AtPath<'p, P, D>, S>
+    where
+        P: AsPathSegment,
+    {
+        Configured::new(
+            AtPath::new(self.deserializer, path),
+            self.options,
+            self.sink,
+        )
+    }
+
+    pub(super) fn into_parts(self) -> (D, Options, S) {
+        (self.deserializer, self.options, self.sink)
+    }
+}
+
+impl<'de, D, S> Configured<D, S>
+where
+    D: Deserializer<'de>,
+    S: SkippedSink,
+{
+    /// Same as [`DeserializerExt::try_fold`](super::DeserializerExt::try_fold).
+    pub fn try_fold<Item, Acc, Err, F>(
+        self,
+        init: Acc,
+        f: F,
+    ) -> Result<ControlFlow<Err, Acc>, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnMut(Acc, Item) -> ControlFlow<Err, Acc>,
+    {
+        try_fold_seq(self, init, f, no_error)
+    }
+
+    /// Same as [`DeserializerExt::fold`](super::DeserializerExt::fold).
+    pub fn fold<Item, Acc, F>(self, init: Acc, mut f: F) -> Result<Acc, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnMut(Acc, Item) -> Acc,
+    {
+        match self.try_fold(init, |acc, item| lift_infallible(f(acc, item))) {
+            Ok(ControlFlow::Break(_infallible)) => unreachable!(),
+            Ok(ControlFlow::Continue(res)) => Ok(res),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Same as [`DeserializerExt::for_each`](super::DeserializerExt::for_each).
+    pub fn for_each<Item, F>(self, mut f: F) -> Result<(), D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnMut(Item),
+    {
+        self.fold((), |(), item| f(item))
+    }
+
+    /// Same as [`DeserializerExt::try_for_each`](super::DeserializerExt::try_for_each).
+    pub fn try_for_each<Item, E, F>(self, mut f: F) -> Result<(), D::Error>
+    where
+        Item: Deserialize<'de>,
+        E: fmt::Display,
+        F: FnMut(Item) -> Result<(), E>,
+    {
+        let fold_res = try_fold_seq(
+            self,
+            (),
+            |(), item| match f(item) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(e) => ControlFlow::Break(e),
+            },
+            break_error,
+        );
+        match fold_res? {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(_) => unreachable!("errors fail the deserialization"),
+        }
+    }
+
+    /// Same as [`DeserializerExt::par_fold`](super::DeserializerExt::par_fold).
+    #[cfg(feature = "std")]
+    pub fn par_fold<Item, Acc, F, M>(self, init: Acc, fold: F, merge: M) -> Result<Acc, D::Error>
+    where
+        Item: Deserialize<'de> + Send,
+        Acc: Clone + Send,
+        F: Fn(Acc, Item) -> Acc + Sync,
+        M: FnMut(Acc, Acc) -> Acc,
+    {
+        // `push` only breaks when the workers have failed, the rest of the input is then
+        // left unread.
+        let options = self.options().drain(Drain::Abort);
+        crate::par::par_fold(
+            || init.clone(),
+            fold,
+            merge,
+            no_error,
+            |push| {
+                self.with_options(options)
+                    .try_fold((), |(), item| push(item))
+                    .map(drop)
+            },
+        )
+    }
+
+    /// Same as [`DeserializerExt::pipelined_try_fold`](super::DeserializerExt::pipelined_try_fold).
+    #[cfg(feature = "std")]
+    pub fn pipelined_try_fold<Item, Acc, Err, F>(
+        self,
+        init: Acc,
+        f: F,
+    ) -> Result<ControlFlow<Err, Acc>, D::Error>
+    where
+        Item: Deserialize<'de> + Send,
+        Acc: Send,
+        Err: Send,
+        F: FnMut(Acc, Item) -> ControlFlow<Err, Acc> + Send,
+    {
+        pipelined::pipelined_try_fold(self, init, f)
+    }
+
+    /// Same as [`DeserializerExt::pipelined_fold`](super::DeserializerExt::pipelined_fold).
+    #[cfg(feature = "std")]
+    pub fn pipelined_fold<Item, Acc, F>(self, init: Acc, mut f: F) -> Result<Acc, D::Error>
+    where
+        Item: Deserialize<'de> + Send,
+        Acc: Send,
+        F: FnMut(Acc, Item) -> Acc + Send,
+    {
+        match self.pipelined_try_fold(init, |acc, item| lift_infallible(f(acc, item))) {
+            Ok(ControlFlow::Break(_infallible)) => unreachable!(),
+            Ok(ControlFlow::Continue(res)) => Ok(res),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Same as [`DeserializerExt::pipelined_for_each`](super::DeserializerExt::pipelined_for_each).
+    #[cfg(feature = "std")]
+    pub fn pipelined_for_each<Item, F>(self, mut f: F) -> Result<(), D::Error>
+    where
+        Item: Deserialize<'de> + Send,
+        F: FnMut(Item) + Send,
+    {
+        self.pipelined_fold((), |(), item| f(item))
+    }
+
+    /// Same as [`DeserializerExt::for_each_in_place`](super::DeserializerExt::for_each_in_place).
+    pub fn for_each_in_place<Item, F>(self, f: F) -> Result<(), D::Error>
+    where
+        Item: Deserialize<'de> + Default,
+        F: FnMut(&mut Item),
+    {
+        let (deserializer, options, sink) = self.into_parts();
+        options.shape.deserialize(
+            deserializer,
+            Wrapper(DeserInPlace {
+                place: InPlace(Item::default()),
+                f,
+                elements: Elements::new(options, sink.into_skipped()),
+            }),
+        )
+    }
+
+    /// Same as [`DeserializerExt::with_iter`](super::DeserializerExt::with_iter).
+    pub fn with_iter<Item, R, F>(self, f: F) -> Result<R, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnOnce(&mut dyn Iterator<Item = Item>) -> R,
+    {
+        let (deserializer, options, sink) = self.into_parts();
+        let mut aborted = None;
+        let elements = Elements::new(options, sink.into_skipped());
+        let res = options.shape.deserialize(
+            deserializer,
+            Wrapper(DeserWithIter::new(f, elements, &mut aborted)),
+        );
+        match aborted {
+            Some(res) => Ok(res),
+            None => res,
+        }
+    }
+
+    /// Same as [`DeserializerExt::find`](super::DeserializerExt::find).
+    pub fn find<Item, F>(self, mut f: F) -> Result<Option<Item>, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: for<'a> FnMut(&'a Item) -> bool,
+    {
+        let fold_res = self.try_fold((), |(), item| {
+            if f(&item) {
+                ControlFlow::Break(item)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        let res = match fold_res? {
+            ControlFlow::Continue(()) => None,
+            ControlFlow::Break(item) => Some(item),
+        };
+        Ok(res)
+    }
+
+    /// Same as [`DeserializerExt::count`](super::DeserializerExt::count).
+    pub fn count<Item>(self) -> Result<usize, D::Error>
+    where
+        Item: Deserialize<'de>,
+    {
+        self.fold(0, |count, _: Item| count + 1)
+    }
+
+    /// Same as [`DeserializerExt::any`](super::DeserializerExt::any).
+    pub fn any<Item, F>(self, mut f: F) -> Result<bool, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnMut(Item) -> bool,
+    {
+        let fold_res = self.try_fold((), |(), item| {
+            if f(item) {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        Ok(fold_res?.is_break())
+    }
+
+    /// Same as [`DeserializerExt::all`](super::DeserializerExt::all).
+    pub fn all<Item, F>(self, mut f: F) -> Result<bool, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnMut(Item) -> bool,
+    {
+        let fold_res = self.try_fold((), |(), item| {
+            if f(item) {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        });
+        Ok(fold_res?.is_continue())
+    }
+
+    /// Same as [`DeserializerExt::position`](super::DeserializerExt::position).
+    pub fn position<Item, F>(self, mut f: F) -> Result<Option<usize>, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnMut(Item) -> bool,
+    {
+        let fold_res = self.try_fold(0, |idx, item| {
+            if f(item) {
+                ControlFlow::Break(idx)
+            } else {
+                ControlFlow::Continue(idx + 1)
+            }
+        });
+        let res = match fold_res? {
+            ControlFlow::Continue(_) => None,
+            ControlFlow::Break(idx) => Some(idx),
+        };
+        Ok(res)
+    }
+
+    /// Same as [`DeserializerExt::find_map`](super::DeserializerExt::find_map).
+    pub fn find_map<Item, B, F>(self, mut f: F) -> Result<Option<B>, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnMut(Item) -> Option<B>,
+    {
+        let fold_res = self.try_fold((), |(), item| match f(item) {
+            Some(res) => ControlFlow::Break(res),
+            None => ControlFlow::Continue(()),
+        });
+        let res = match fold_res? {
+            ControlFlow::Continue(()) => None,
+            ControlFlow::Break(res) => Some(res),
+        };
+        Ok(res)
+    }
+
+    /// Same as [`DeserializerExt::last`](super::DeserializerExt::last).
+    pub fn last<Item>(self) -> Result<Option<Item>, D::Error>
+    where
+        Item: Deserialize<'de>,
+    {
+        self.fold(None, |_, item| Some(item))
+    }
+
+    /// Same as [`DeserializerExt::nth`](super::DeserializerExt::nth).
+    pub fn nth<Item>(self, n: usize) -> Result<Option<Item>, D::Error>
+    where
+        Item: Deserialize<'de>,
+    {
+        let fold_res = self.try_fold(n, |n, item| {
+            if n == 0 {
+                ControlFlow::Break(item)
+            } else {
+                ControlFlow::Continue(n - 1)
+            }
+        });
+        let res = match fold_res? {
+            ControlFlow::Continue(_) => None,
+            ControlFlow::Break(item) => Some(item),
+        };
+        Ok(res)
+    }
+
+    /// Same as [`DeserializerExt::min_by_key`](super::DeserializerExt::min_by_key).
+    pub fn min_by_key<Item, B, F>(self, mut f: F) -> Result<Option<Item>, D::Error>
+    where
+        Item: Deserialize<'de>,
+        B: Ord,
+        F: FnMut(&Item) -> B,
+    {
+        let res = self.fold(None, |min: Option<(B, Item)>, item| {
+            let key = f(&item);
+            match min {
+                Some((min_key, min)) if min_key <= key => Some((min_key, min)),
+                _ => Some((key, item)),
+            }
+        })?;
+        Ok(res.map(|(_, item)| item))
+    }
+
+    /// Same as [`DeserializerExt::max_by_key`](super::DeserializerExt::max_by_key).
+    pub fn max_by_key<Item, B, F>(self, mut f: F) -> Result<Option<Item>, D::Error>
+    where
+        Item: Deserialize<'de>,
+        B: Ord,
+        F: FnMut(&Item) -> B,
+    {
+        let res = self.fold(None, |max: Option<(B, Item)>, item| {
+            let key = f(&item);
+            match max {
+                Some((max_key, max)) if max_key > key => Some((max_key, max)),
+                _ => Some((key, item)),
+            }
+        })?;
+        Ok(res.map(|(_, item)| item))
+    }
+
+    /// Same as [`DeserializerExt::sum`](super::DeserializerExt::sum).
+    pub fn sum<Item, T>(self) -> Result<T, D::Error>
+    where
+        Item: Deserialize<'de>,
+        T: Sum<Item>,
+    {
+        self.with_iter(|iter| iter.sum())
+    }
+
+    /// Same as [`DeserializerExt::product`](super::DeserializerExt::product).
+    pub fn product<Item, P>(self) -> Result<P, D::Error>
+    where
+        Item: Deserialize<'de>,
+        P: Product<Item>,
+    {
+        self.with_iter(|iter| iter.product())
+    }
+
+    /// Same as [`DeserializerExt::reduce`](super::DeserializerExt::reduce).
+    pub fn reduce<Item, F>(self, mut f: F) -> Result<Option<Item>, D::Error>
+    where
+        Item: Deserialize<'de>,
+        F: FnMut(Item, Item) -> Item,
+    {
+        self.fold(None, |acc, item| match acc {
+            None => Some(item),
+            Some(acc) => Some(f(acc, item)),
+        })
+    }
+
+    /// Same as [`DeserializerExt::partition`](super::DeserializerExt::partition).
+    pub fn partition<Item, B, F>(self, mut f: F) -> Result<(B, B), D::Error>
+    where
+        Item: Deserialize<'de>,
+        B: Default + Extend<Item>,
+        F: FnMut(&Item) -> bool,
+    {
+        self.fold(
+            (B::default(), B::default()),
+            |(mut matching, mut others), item| {
+                if f(&item) {
+                    matching.extend(Some(item));
+                } else {
+                    others.extend(Some(item));
+                }
+                (matching, others)
+            },
+        )
+    }
+
+    /// Same as [`DeserializerExt::aggregate`](super::DeserializerExt::aggregate).
+    pub fn aggregate<A>(self) -> Result<A::Value, D::Error>
+    where
+        A: Aggregator,
+        A::Item: Deserialize<'de>,
+    {
+        try_fold_seq(self, A::init(), A::try_fold, A::error).map(A::finalize)
+    }
+
+    /// Same as [`DeserializerExt::fold_tuple`](super::DeserializerExt::fold_tuple).
+    pub fn fold_tuple<Item, Acc, T>(self, init: Acc, mut fs: T) -> Result<Acc, D::Error>
+    where
+        Item: Deserialize<'de>,
+        T: FoldTuple<Item, Acc>,
+    {
+        self.fold(init, |acc, item| fs.step(acc, &item))
+    }
+
+    /// Same as [`DeserializerExt::try_fold_tuple`](super::DeserializerExt::try_fold_tuple).
+    pub fn try_fold_tuple<Item, Init, T>(self, init: Init, mut fs: T) -> Result<T::Output, D::Error>
+    where
+        Item: Deserialize<'de>,
+        T: TryFoldTuple<Item, Init>,
+    {
+        let fold_res = self.try_fold(T::start(init), |state, item| fs.step(state, &item))?;
+        match fold_res {
+            ControlFlow::Continue(state) | ControlFlow::Break(state) => Ok(state),
+        }
+    }
+
+    /// Same as [`MapDeserializerExt::try_fold_entries`](super::MapDeserializerExt::try_fold_entries).
+    pub fn try_fold_entries<K, V, Acc, Err, F>(
+        self,
+        init: Acc,
+        f: F,
+    ) -> Result<ControlFlow<Err, Acc>, D::Error>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        F: FnMut(Acc, (K, V)) -> ControlFlow<Err, Acc>,
+    {
+        let (deserializer, options, sink) = self.into_parts();
+        let mut aborted = None;
+        let elements = Elements::new(options, sink.into_skipped());
+        let folder = DeserTryFolder::new(init, f, no_error, elements, &mut aborted);
+        let res = deserializer.deserialize_map(MapWrapper(folder));
+        match aborted {
+            Some(clot_break) => Ok(ControlFlow::Break(clot_break)),
+            None => res,
+        }
+    }
+
+    /// Same as [`MapDeserializerExt::fold_entries`](super::MapDeserializerExt::fold_entries).
+    pub fn fold_entries<K, V, Acc, F>(self, init: Acc, mut f: F) -> Result<Acc, D::Error>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        F: FnMut(Acc, (K, V)) -> Acc,
+    {
+        match self.try_fold_entries(init, |acc, entry| lift_infallible(f(acc, entry))) {
+            Ok(ControlFlow::Break(_infallible)) => unreachable!(),
+            Ok(ControlFlow::Continue(res)) => Ok(res),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Same as [`MapDeserializerExt::for_each_entry`](super::MapDeserializerExt::for_each_entry).
+    pub fn for_each_entry<K, V, F>(self, mut f: F) -> Result<(), D::Error>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        F: FnMut((K, V)),
+    {
+        self.fold_entries((), |(), entry| f(entry))
+    }
+
+    /// Same as [`MapDeserializerExt::find_entry`](super::MapDeserializerExt::find_entry).
+    pub fn find_entry<K, V, F>(self, mut f: F) -> Result<Option<(K, V)>, D::Error>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        F: for<'a> FnMut(&'a (K, V)) -> bool,
+    {
+        let fold_res = self.try_fold_entries((), |(), entry| {
+            if f(&entry) {
+                ControlFlow::Break(entry)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        let res = match fold_res? {
+            ControlFlow::Continue(()) => None,
+            ControlFlow::Break(entry) => Some(entry),
+        };
+        Ok(res)
+    }
+}
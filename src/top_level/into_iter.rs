@@ -0,0 +1,196 @@
+use core::{iter::FusedIterator, ops::ControlFlow};
+use std::{
+    panic,
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+};
+
+use serde::{de::DeserializeOwned, Deserializer};
+
+use super::AdaptersExt;
+use crate::{Drain, Options};
+
+/// Number of items buffered by [`into_iter`] ahead of the consumer.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Iterate over the items of a sequence, deserialized on a background thread.
+///
+/// The format deserializer is built on the worker thread by `factory`, and the sequence is
+/// aggregated there with [`DeserializerExt::try_fold`](super::DeserializerExt::try_fold), the items being sent to the
+/// returned iterator through a channel of [`DEFAULT_CAPACITY`] items.
+/// Contrary to the methods of [`DeserializerExt`](super::DeserializerExt), this allows to interleave the items
+/// of several sequences, for instance to merge them.
+///
+/// ```
+/// use serde_deser_iter::top_level::into_iter;
+///
+/// let mut evens = into_iter::<u32, _, _, _>(|| serde_json::Deserializer::from_str("[0, 2, 4]"));
+/// let mut odds = into_iter::<u32, _, _, _>(|| serde_json::Deserializer::from_str("[1, 3, 5]"));
+/// let mut merged = Vec::new();
+/// while let (Some(even), Some(odd)) = (evens.next(), odds.next()) {
+///     merged.extend([even.unwrap(), odd.unwrap()]);
+/// }
+/// assert_eq!(merged, [0, 1, 2, 3, 4, 5]);
+/// ```
+pub fn into_iter<Item, D, E, F>(factory: F) -> IntoIter<Item, E>
+where
+    Item: DeserializeOwned + Send + 'static,
+    E: Send + 'static,
+    F: FnOnce() -> D + Send + 'static,
+    for<'a> &'a mut D: Deserializer<'static, Error = E>,
+{
+    IntoIter::spawn(Options::new(), DEFAULT_CAPACITY, factory)
+}
+
+/// An owned iterator over the items of a sequence, see [`into_iter`].
+///
+/// It yields the items in order, followed by the deserialization error if any.
+///
+/// ```
+/// use serde_deser_iter::top_level::into_iter;
+///
+/// let mut iter = into_iter::<u32, _, _, _>(|| serde_json::Deserializer::from_str(r#"[1, "two", 3]"#));
+/// assert_eq!(iter.next().unwrap().unwrap(), 1);
+/// assert!(iter.next().unwrap().is_err());
+/// assert!(iter.next().is_none());
+/// ```
+///
+/// Dropping it before the end of the sequence stops the worker thread, which does not
+/// read the rest of the input, and waits for it to finish.
+///
+/// ```
+/// use std::io::{self, Read};
+///
+/// use serde_deser_iter::top_level::into_iter;
+///
+/// /// The endless sequence `[1,1,1,...`.
+/// struct Ones(usize);
+///
+/// impl Read for Ones {
+///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+///         for byte in buf.iter_mut() {
+///             *byte = [b'[', b'1', b','][self.0];
+///             self.0 = if self.0 == 1 { 2 } else { 1 };
+///         }
+///         Ok(buf.len())
+///     }
+/// }
+///
+/// let mut iter = into_iter::<u32, _, _, _>(|| serde_json::Deserializer::from_reader(Ones(0)));
+/// assert_eq!(iter.next().unwrap().unwrap(), 1);
+/// // Returns once the worker has stopped reading the input.
+/// drop(iter);
+/// ```
+///
+/// A panic of the worker thread, for instance in `factory`, is forwarded to the consumer
+/// when the iterator reaches its end.
+///
+/// ```
+/// use std::panic::{self, AssertUnwindSafe};
+///
+/// use serde_deser_iter::top_level::into_iter;
+/// use serde_json::{de::StrRead, Deserializer};
+///
+/// let mut iter = into_iter::<u32, _, _, _>(|| -> Deserializer<StrRead<'static>> {
+///     panic!("no input")
+/// });
+/// let payload = panic::catch_unwind(AssertUnwindSafe(|| iter.next())).unwrap_err();
+/// assert_eq!(payload.downcast_ref::<&str>(), Some(&"no input"));
+/// ```
+pub struct IntoIter<Item, E> {
+    receiver: Option<Receiver<Result<Item, E>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<Item, E> IntoIter<Item, E>
+where
+    Item: DeserializeOwned + Send + 'static,
+    E: Send + 'static,
+{
+    /// Same as [`into_iter`], processing the sequence with `options` and buffering up to
+    /// `capacity` items ahead of the consumer.
+    ///
+    /// The [`Drain`] strategy of the options is ignored: the worker always stops reading
+    /// the input when the iterator is dropped.
+    pub fn spawn<D, F>(options: Options, capacity: usize, factory: F) -> Self
+    where
+        F: FnOnce() -> D + Send + 'static,
+        for<'a> &'a mut D: Deserializer<'static, Error = E>,
+    {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let worker = thread::spawn(move || {
+            work(options, factory, |item| sender.send(item).is_ok());
+        });
+        Self {
+            receiver: Some(receiver),
+            worker: Some(worker),
+        }
+    }
+}
+
+impl<Item, E> IntoIter<Item, E> {
+    /// Wait for the worker thread, forwarding its panic if it did.
+    fn join(&mut self) {
+        self.receiver = None;
+        if let Some(worker) = self.worker.take() {
+            if let Err(payload) = worker.join() {
+                panic::resume_unwind(payload);
+            }
+        }
+    }
+}
+
+/// The body of the worker thread, passing the items and the error to `send`, which
+/// returns `false` once the consumer is gone.
+pub(super) fn work<Item, D, E, F, S>(options: Options, factory: F, mut send: S)
+where
+    Item: DeserializeOwned,
+    F: FnOnce() -> D,
+    for<'a> &'a mut D: Deserializer<'static, Error = E>,
+    S: FnMut(Result<Item, E>) -> bool,
+{
+    let mut deserializer = factory();
+    let res = (&mut deserializer)
+        .with_options(options.drain(Drain::Abort))
+        .try_fold((), |(), item: Item| {
+            if send(Ok(item)) {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        });
+    if let Err(e) = res {
+        send(Err(e));
+    }
+}
+
+impl<Item, E> Iterator for IntoIter<Item, E> {
+    type Item = Result<Item, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.receiver.as_ref()?.recv();
+        match res {
+            Ok(Ok(item)) => Some(Ok(item)),
+            Ok(Err(e)) => {
+                self.join();
+                Some(Err(e))
+            }
+            Err(mpsc::RecvError) => {
+                self.join();
+                None
+            }
+        }
+    }
+}
+
+impl<Item, E> FusedIterator for IntoIter<Item, E> {}
+
+impl<Item, E> Drop for IntoIter<Item, E> {
+    fn drop(&mut self) {
+        self.receiver = None;
+        if let Some(worker) = self.worker.take() {
+            // The panic of the worker, if any, is not forwarded while dropping.
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,1118 @@
+//! Zero-allocation deserialization of sequences located at the top-level of the data file
+//!
+//! This modules provides mainly one trait, [`DeserializerExt`] which extends [`serde::Deserializer`]
+//! with methods similar to those used to aggregate data from an iterator
+//! (`fold`, `for_each`, `find`, `count`, `any`, `max_by_key`...), as well as a scoped
+//! external iterator (`with_iter`).
+//! Top-level maps can be processed entry by entry in the same way with [`MapDeserializerExt`]
+//! (`fold_entries`, `for_each_entry`, `find_entry`).
+//! Sequences nested deeper in the data file can be reached with [`AdaptersExt::at_path`],
+//! and the way they are processed is set with [`AdaptersExt::with_options`].
+//! With the `std` feature, `par_fold` folds the items on several threads, and `into_iter`
+//! turns a sequence into an owned iterator, by deserializing it on a background thread.
+//! `Sources` aggregates the sequences of several data files and merges the results.
+//! With the `futures` feature, `into_stream` turns it into an asynchronous stream in the same way.
+//!
+//! To use it, simply `use serde_deser_iter::top_level::DeserializerExt` and use the appropriated method
+//! from [`DeserializerExt`].
+//!
+//! # Example
+//!
+//! Given the following JSON:
+//!
+//! ```json
+//! [
+//!     {"id": 0, "name": "bob", "subscribed_to": ["rust", "knitting", "cooking"]},
+//!     {"id": 1, "name": "toby 🐶", "subscribed_to": ["sticks", "tennis-balls"]},
+//!     {"id": 2, "name": "alice", "subscribed_to": ["rust", "hiking", "paris"]},
+//!     {"id": 3, "name": "mark", "subscribed_to": ["rust", "rugby", "doctor-who"]},
+//!     {"id": 4, "name": "vera", "subscribed_to": ["rust", "mma", "philosophy"]}
+//! ]
+//! ```
+//! we can process it without allocating a 5-sized vector of items as follow:
+//!
+//! ```
+//! use serde_deser_iter::top_level::DeserializerExt;
+//! # use std::{fs::File, io::BufReader, path::PathBuf};
+//!
+//! #[derive(serde::Deserialize)]
+//! struct DataEntry {
+//!     id: u32,
+//!     name: String,
+//!     subscribed_to: Vec<String>,
+//! }
+//!
+//! fn main() -> anyhow::Result<()> {
+//! #    let example_json_path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "examples", "top_level_data.json"]
+//! #       .iter()
+//! #       .collect();
+//!     let buffered_file: BufReader<File> = BufReader::new(File::open(example_json_path)?);
+//!     let mut json_deserializer = serde_json::Deserializer::from_reader(buffered_file);
+//!     
+//!     let search_result: Result<Option<DataEntry>, serde_json::Error>;
+//!     search_result =
+//!         json_deserializer.find(|entry: &DataEntry| !entry.subscribed_to.contains(&"rust".into()));
+//!     match search_result? {
+//!         Some(entry) => println!(
+//!             "Looks like {} (id: {}) doesn't like Rust... Good boy status revoked.",
+//!             entry.name, entry.id
+//!         ),
+//!         None => print!("Everybody likes Rust. How cool!"),
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use core::{
+    convert::Infallible,
+    fmt,
+    iter::{Product, Sum},
+    marker::PhantomData,
+    ops::ControlFlow,
+};
+
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::{
+    deep::{as_ref, Aggregator, ErrorFn},
+    elements::{self, ElementSeed, Elements, SkippedSink},
+    Drain, Options,
+};
+
+mod path;
+pub use path::*;
+
+mod configured;
+pub use configured::*;
+
+mod tuple;
+pub use tuple::*;
+
+#[cfg(feature = "std")]
+mod into_iter;
+#[cfg(feature = "std")]
+pub use into_iter::*;
+
+#[cfg(feature = "std")]
+mod pipelined;
+
+#[cfg(feature = "std")]
+mod sources;
+#[cfg(feature = "std")]
+pub use sources::*;
+
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "futures")]
+pub use stream::*;
+
+struct DeserTryFolder<'a, Acc, Item, Err, F> {
+    #[allow(clippy::type_complexity)]
+    marker: PhantomData<fn(Acc, Item) -> ControlFlow<Err, Acc>>,
+    init: Acc,
+    f: F,
+    error: ErrorFn<Err, Acc>,
+    elements: Elements<'a>,
+    aborted: &'a mut Option<Err>,
+}
+
+impl<'a, Acc, Item, Err, F> DeserTryFolder<'a, Acc, Item, Err, F> {
+    pub fn new(
+        init: Acc,
+        f: F,
+        error: ErrorFn<Err, Acc>,
+        elements: Elements<'a>,
+        aborted: &'a mut Option<Err>,
+    ) -> Self {
+        Self {
+            marker: PhantomData,
+            f,
+            init,
+            error,
+            elements,
+            aborted,
+        }
+    }
+}
+
+struct Wrapper<T>(T);
+
+impl<'de, 'a, Acc, Item, Err, F> Visitor<'de> for Wrapper<DeserTryFolder<'a, Acc, Item, Err, F>>
+where
+    F: FnMut(Acc, Item) -> ControlFlow<Err, Acc>,
+    Item: Deserialize<'de>,
+{
+    type Value = ControlFlow<Err, Acc>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.elements.options().shape.expecting(formatter)
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut acc = self.0.init;
+        while let Some(value) = self.0.elements.next(&mut seq)? {
+            let state = (self.0.f)(acc, value);
+            if let Some(e) = (self.0.error)(as_ref(&state)) {
+                return Err(self.0.elements.error(e));
+            }
+            match state {
+                ControlFlow::Continue(new_acc) => acc = new_acc,
+                ControlFlow::Break(clot_break) => {
+                    let drain = self.0.elements.options().drain;
+                    if drain == Drain::Abort {
+                        *self.0.aborted = Some(clot_break);
+                        return Err(Drain::abort_error());
+                    }
+                    drain.drain::<Item, _>(&mut seq)?;
+                    return Ok(ControlFlow::Break(clot_break));
+                }
+            }
+        }
+        Ok(ControlFlow::Continue(acc))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let shape = self.0.elements.options().shape;
+        elements::visit_map_values(self, shape, map)
+    }
+}
+
+struct MapWrapper<T>(T);
+
+impl<'de, 'a, Acc, K, V, Err, F> Visitor<'de>
+    for MapWrapper<DeserTryFolder<'a, Acc, (K, V), Err, F>>
+where
+    F: FnMut(Acc, (K, V)) -> ControlFlow<Err, Acc>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = ControlFlow<Err, Acc>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(mut self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut acc = self.0.init;
+        while let Some(entry) = self.0.elements.next_entry(&mut map)? {
+            match (self.0.f)(acc, entry) {
+                ControlFlow::Continue(new_acc) => acc = new_acc,
+                ControlFlow::Break(clot_break) => {
+                    let drain = self.0.elements.options().drain;
+                    if drain == Drain::Abort {
+                        *self.0.aborted = Some(clot_break);
+                        return Err(Drain::abort_error());
+                    }
+                    drain.drain_map::<K, V, _>(&mut map)?;
+                    return Ok(ControlFlow::Break(clot_break));
+                }
+            }
+        }
+        Ok(ControlFlow::Continue(acc))
+    }
+}
+
+struct DeserWithIter<'a, Item, R, F> {
+    marker: PhantomData<fn(Item) -> R>,
+    f: F,
+    elements: Elements<'a>,
+    aborted: &'a mut Option<R>,
+}
+
+impl<'a, Item, R, F> DeserWithIter<'a, Item, R, F> {
+    pub fn new(f: F, elements: Elements<'a>, aborted: &'a mut Option<R>) -> Self {
+        Self {
+            marker: PhantomData,
+            f,
+            elements,
+            aborted,
+        }
+    }
+}
+
+/// Iterator over the items of a [`SeqAccess`], storing the first error encountered.
+struct SeqIter<'de, 'a, A, Item>
+where
+    A: SeqAccess<'de>,
+{
+    marker: PhantomData<fn(&'de ()) -> Item>,
+    seq: A,
+    elements: Elements<'a>,
+    error: Option<A::Error>,
+    finished: bool,
+}
+
+impl<'de, 'a, A, Item> Iterator for SeqIter<'de, 'a, A, Item>
+where
+    A: SeqAccess<'de>,
+    Item: Deserialize<'de>,
+{
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.elements.next(&mut self.seq) {
+            Ok(Some(item)) => Some(item),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match (self.finished, self.seq.size_hint()) {
+            (true, _) => (0, Some(0)),
+            // Items may be skipped, so the length hinted by the format is only an upper bound.
+            (false, Some(n)) => (0, Some(n)),
+            (false, None) => (0, None),
+        }
+    }
+}
+
+impl<'de, 'a, Item, R, F> Visitor<'de> for Wrapper<DeserWithIter<'a, Item, R, F>>
+where
+    F: FnOnce(&mut dyn Iterator<Item = Item>) -> R,
+    Item: Deserialize<'de>,
+{
+    type Value = R;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.elements.options().shape.expecting(formatter)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut iter = SeqIter {
+            marker: PhantomData,
+            seq,
+            elements: self.0.elements,
+            error: None,
+            finished: false,
+        };
+        let res = (self.0.f)(&mut iter);
+        if let Some(e) = iter.error {
+            return Err(e);
+        }
+        if !iter.finished {
+            let drain = iter.elements.options().drain;
+            if drain == Drain::Abort {
+                *self.0.aborted = Some(res);
+                return Err(Drain::abort_error());
+            }
+            drain.drain::<Item, _>(&mut iter.seq)?;
+        }
+        Ok(res)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let shape = self.0.elements.options().shape;
+        elements::visit_map_values(self, shape, map)
+    }
+}
+
+/// Seed refilling an existing value with [`Deserialize::deserialize_in_place`].
+struct InPlace<T>(T);
+
+impl<'de, T> ElementSeed<'de> for InPlace<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D>(&mut self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize_in_place(deserializer, &mut self.0)
+    }
+}
+
+struct DeserInPlace<'a, Item, F> {
+    place: InPlace<Item>,
+    f: F,
+    elements: Elements<'a>,
+}
+
+impl<'de, 'a, Item, F> Visitor<'de> for Wrapper<DeserInPlace<'a, Item, F>>
+where
+    F: FnMut(&mut Item),
+    Item: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.elements.options().shape.expecting(formatter)
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        while self
+            .0
+            .elements
+            .next_seed(&mut seq, &mut self.0.place)?
+            .is_some()
+        {
+            (self.0.f)(&mut self.0.place.0);
+        }
+        Ok(())
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let shape = self.0.elements.options().shape;
+        elements::visit_map_values(self, shape, map)
+    }
+}
+
+fn lift_infallible<T>(val: T) -> ControlFlow<Infallible, T> {
+    ControlFlow::Continue(val)
+}
+
+/// The [`ErrorFn`] of folds whose early returns are all errors.
+fn break_error<'s, Err: fmt::Display, Acc>(
+    state: ControlFlow<&'s Err, &'s Acc>,
+) -> Option<&'s dyn fmt::Display> {
+    match state {
+        ControlFlow::Break(e) => Some(e),
+        ControlFlow::Continue(_) => None,
+    }
+}
+
+/// Implementation of [`DeserializerExt::try_fold`], failing on the states for which
+/// `error` returns an error.
+fn try_fold_seq<'de, D, S, Item, Acc, Err, F>(
+    deserializer: Configured<D, S>,
+    init: Acc,
+    f: F,
+    error: ErrorFn<Err, Acc>,
+) -> Result<ControlFlow<Err, Acc>, D::Error>
+where
+    D: Deserializer<'de>,
+    S: SkippedSink,
+    Item: Deserialize<'de>,
+    F: FnMut(Acc, Item) -> ControlFlow<Err, Acc>,
+{
+    let (deserializer, options, sink) = deserializer.into_parts();
+    let mut aborted = None;
+    let elements = Elements::new(options, sink.into_skipped());
+    let folder = DeserTryFolder::new(init, f, error, elements, &mut aborted);
+    let res = options.shape.deserialize(deserializer, Wrapper(folder));
+    match aborted {
+        Some(clot_break) => Ok(ControlFlow::Break(clot_break)),
+        None => res,
+    }
+}
+
+/// The workhorse of this module.
+///
+/// See module-level [doc](`crate::top_level`) for a broad level explanation.
+///
+/// It is implemented for all [`Deserializer`]s and all item types. The options used
+/// to process the sequence are set with [`AdaptersExt::with_options`], which returns
+/// a [`Configured`] deserializer offering the same methods.
+pub trait DeserializerExt<'de, Item>: Deserializer<'de>
+where
+    Item: Deserialize<'de>,
+{
+    /// Aggregate all items from the sequence using a fallible/early-returning function.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn try_fold<Acc, Err, F>(self, init: Acc, f: F) -> Result<ControlFlow<Err, Acc>, Self::Error>
+    where
+        F: FnMut(Acc, Item) -> ControlFlow<Err, Acc>,
+    {
+        Configured::wrap(self).try_fold(init, f)
+    }
+
+    /// Aggregate all items from the sequence. If the function may fail or needs to return early
+    /// use [try_fold](`DeserializerExt::try_fold`).
+    fn fold<Acc, F>(self, init: Acc, f: F) -> Result<Acc, Self::Error>
+    where
+        F: FnMut(Acc, Item) -> Acc,
+    {
+        Configured::wrap(self).fold(init, f)
+    }
+
+    /// Run a cloture with side-effects on all items of the sequence.
+    fn for_each<F>(self, f: F) -> Result<(), Self::Error>
+    where
+        F: FnMut(Item),
+    {
+        Configured::wrap(self).for_each(f)
+    }
+
+    /// Run a fallible cloture with side-effects on all items of the sequence.
+    ///
+    /// The first error returned by the closure fails the deserialization, and is converted
+    /// to the deserializer's error through [`serde::de::Error::custom`]. The error is located
+    /// at the index of the item when the options ask for [context](Options::error_context).
+    ///
+    /// ```
+    /// use serde_deser_iter::{top_level::AdaptersExt, Options};
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str("[3, 1, -4, 1]");
+    /// let mut total = 0;
+    /// let res = json_deserializer
+    ///     .with_options(Options::new().error_context(true))
+    ///     .try_for_each(|quantity: i32| {
+    ///         if quantity < 0 {
+    ///             return Err(format!("negative quantity {quantity}"));
+    ///         }
+    ///         total += quantity;
+    ///         Ok(())
+    ///     });
+    /// assert_eq!(
+    ///     res.unwrap_err().to_string(),
+    ///     "[2]: negative quantity -4 at line 1 column 11"
+    /// );
+    /// assert_eq!(total, 4);
+    /// ```
+    fn try_for_each<E, F>(self, f: F) -> Result<(), Self::Error>
+    where
+        E: fmt::Display,
+        F: FnMut(Item) -> Result<(), E>,
+    {
+        Configured::wrap(self).try_for_each(f)
+    }
+
+    /// Aggregate all items from the sequence on a pool of worker threads.
+    ///
+    /// The items are deserialized on the current thread, and dispatched by batches to one
+    /// worker per available core, which is worth it when the work done on each item outweighs
+    /// their deserialization. Each worker folds its items with `fold`, starting from a clone
+    /// of `init`, and the accumulators of all workers are combined with `merge` at the end.
+    /// The items are not dispatched in order, the accumulators are merged in any order,
+    /// and `init` should therefore be neutral for `merge`.
+    ///
+    /// ```
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"["rust", "knitting", "rust"]"#);
+    /// let rust_count = json_deserializer
+    ///     .par_fold(
+    ///         0,
+    ///         |count, channel: String| count + usize::from(channel == "rust"),
+    ///         |a, b| a + b,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(rust_count, 2);
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"["rust", 2]"#);
+    /// let res = json_deserializer.par_fold(0, |count, _: String| count + 1, |a, b| a + b);
+    /// assert!(res.is_err());
+    /// ```
+    ///
+    /// A panic of `fold` is forwarded to the caller once the workers have stopped, without
+    /// reading the rest of the input:
+    ///
+    /// ```
+    /// use std::{
+    ///     io::{self, Read},
+    ///     panic::{self, AssertUnwindSafe},
+    /// };
+    ///
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// /// The endless sequence `[1,1,1,...`.
+    /// struct Ones(usize);
+    ///
+    /// impl Read for Ones {
+    ///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    ///         for byte in buf.iter_mut() {
+    ///             *byte = [b'[', b'1', b','][self.0];
+    ///             self.0 = if self.0 == 1 { 2 } else { 1 };
+    ///         }
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_reader(Ones(0));
+    /// let res = panic::catch_unwind(AssertUnwindSafe(|| {
+    ///     json_deserializer.par_fold(0, |_, _: u32| -> u32 { panic!("fold failed") }, |a, b| a + b)
+    /// }));
+    /// assert_eq!(res.unwrap_err().downcast_ref::<&str>(), Some(&"fold failed"));
+    /// ```
+    #[cfg(feature = "std")]
+    fn par_fold<Acc, F, M>(self, init: Acc, fold: F, merge: M) -> Result<Acc, Self::Error>
+    where
+        Item: Send,
+        Acc: Clone + Send,
+        F: Fn(Acc, Item) -> Acc + Sync,
+        M: FnMut(Acc, Acc) -> Acc,
+    {
+        Configured::wrap(self).par_fold(init, fold, merge)
+    }
+
+    /// Same as [`try_fold`](DeserializerExt::try_fold), running `f` on another thread than
+    /// the deserialization of the items.
+    ///
+    /// The items are handed by batches to the thread running `f`, in order, so that their
+    /// deserialization and their processing overlap, which is worth it when both take
+    /// a similar time. Items are therefore read ahead of an early return, and they are then
+    /// discarded. As with `try_fold`, a deserialization error in the rest of the input is
+    /// still reported after an early return, unless the [`Drain::Abort`] option stops reading
+    /// it, the items read ahead being deserialized to the item type in any case.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    ///
+    /// use serde_deser_iter::{
+    ///     top_level::{AdaptersExt, DeserializerExt},
+    ///     Drain, Options,
+    /// };
+    ///
+    /// // The input is truncated after the item we are looking for.
+    /// let mut json_deserializer = serde_json::Deserializer::from_str("[1, 2, 3, ");
+    /// let res = json_deserializer.pipelined_try_fold((), |(), item: u32| {
+    ///     if item == 2 {
+    ///         ControlFlow::Break(item)
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// });
+    /// assert!(res.is_err());
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str("[1, 2, 3, ");
+    /// let res = json_deserializer
+    ///     .with_options(Options::new().drain(Drain::Abort))
+    ///     .pipelined_try_fold((), |(), item: u32| {
+    ///         if item == 2 {
+    ///             ControlFlow::Break(item)
+    ///         } else {
+    ///             ControlFlow::Continue(())
+    ///         }
+    ///     });
+    /// assert_eq!(res.unwrap(), ControlFlow::Break(2));
+    /// ```
+    ///
+    /// A panic of `f` is forwarded to the caller, without reading the rest of the input:
+    ///
+    /// ```
+    /// use std::{
+    ///     io::{self, Read},
+    ///     ops::ControlFlow,
+    ///     panic::{self, AssertUnwindSafe},
+    /// };
+    ///
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// /// The endless sequence `[1,1,1,...`.
+    /// struct Ones(usize);
+    ///
+    /// impl Read for Ones {
+    ///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    ///         for byte in buf.iter_mut() {
+    ///             *byte = [b'[', b'1', b','][self.0];
+    ///             self.0 = if self.0 == 1 { 2 } else { 1 };
+    ///         }
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_reader(Ones(0));
+    /// let res = panic::catch_unwind(AssertUnwindSafe(|| {
+    ///     json_deserializer.pipelined_try_fold((), |(), _: u32| -> ControlFlow<()> {
+    ///         panic!("f failed")
+    ///     })
+    /// }));
+    /// assert_eq!(res.unwrap_err().downcast_ref::<&str>(), Some(&"f failed"));
+    /// ```
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    #[cfg(feature = "std")]
+    fn pipelined_try_fold<Acc, Err, F>(
+        self,
+        init: Acc,
+        f: F,
+    ) -> Result<ControlFlow<Err, Acc>, Self::Error>
+    where
+        Item: Send,
+        Acc: Send,
+        Err: Send,
+        F: FnMut(Acc, Item) -> ControlFlow<Err, Acc> + Send,
+    {
+        Configured::wrap(self).pipelined_try_fold(init, f)
+    }
+
+    /// Same as [`fold`](DeserializerExt::fold), running `f` on another thread than the
+    /// deserialization of the items, see [`pipelined_try_fold`](DeserializerExt::pipelined_try_fold).
+    ///
+    /// ```
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"["bob", "toby", "alice"]"#);
+    /// let names = json_deserializer
+    ///     .pipelined_fold(String::new(), |acc, name: String| acc + &name[..1])
+    ///     .unwrap();
+    /// assert_eq!(names, "bta");
+    /// ```
+    #[cfg(feature = "std")]
+    fn pipelined_fold<Acc, F>(self, init: Acc, f: F) -> Result<Acc, Self::Error>
+    where
+        Item: Send,
+        Acc: Send,
+        F: FnMut(Acc, Item) -> Acc + Send,
+    {
+        Configured::wrap(self).pipelined_fold(init, f)
+    }
+
+    /// Same as [`for_each`](DeserializerExt::for_each), running `f` on another thread than the
+    /// deserialization of the items, see [`pipelined_try_fold`](DeserializerExt::pipelined_try_fold).
+    #[cfg(feature = "std")]
+    fn pipelined_for_each<F>(self, f: F) -> Result<(), Self::Error>
+    where
+        Item: Send,
+        F: FnMut(Item) + Send,
+    {
+        Configured::wrap(self).pipelined_for_each(f)
+    }
+
+    /// Run a cloture with side-effects on all items of the sequence, deserializing each of
+    /// them in place of the previous one.
+    ///
+    /// A single `Item` is kept alive during the whole sequence and refilled with
+    /// [`Deserialize::deserialize_in_place`], so the buffers of its `String`s and `Vec`s are
+    /// reused from one item to the next. The closure only gets a reference to the item,
+    /// and should not rely on its content between two calls.
+    ///
+    /// Derived implementations of [`Deserialize`] only reuse the existing value when the
+    /// `deserialize_in_place` feature of `serde_derive` is enabled, and otherwise replace it.
+    ///
+    /// ```
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// let json = r#"[["rust", "knitting"], ["sticks"], ["rust", "hiking", "paris"]]"#;
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(json);
+    /// let mut longest = 0;
+    /// json_deserializer
+    ///     .for_each_in_place(|channels: &mut Vec<String>| longest = longest.max(channels.len()))
+    ///     .unwrap();
+    /// assert_eq!(longest, 3);
+    /// ```
+    fn for_each_in_place<F>(self, f: F) -> Result<(), Self::Error>
+    where
+        Item: Default,
+        F: FnMut(&mut Item),
+    {
+        Configured::wrap(self).for_each_in_place(f)
+    }
+
+    /// Run a cloture on an (external) iterator over the items of the sequence.
+    ///
+    /// This allows to use all the adapters of [`Iterator`] on the items.
+    /// If a deserialization error occurs, the iterator stops and the error is
+    /// returned once the closure has finished. Items not consumed by the closure
+    /// are drained afterwards.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies
+    /// to the items not consumed by the closure.
+    ///
+    /// ```
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str("[1, 2, 3, 4, 5, 6]");
+    /// let sum: u32 = json_deserializer
+    ///     .with_iter(|iter| iter.filter(|x: &u32| x % 2 == 0).take(2).sum())
+    ///     .unwrap();
+    /// assert_eq!(sum, 6);
+    /// ```
+    fn with_iter<R, F>(self, f: F) -> Result<R, Self::Error>
+    where
+        F: FnOnce(&mut dyn Iterator<Item = Item>) -> R,
+    {
+        Configured::wrap(self).with_iter(f)
+    }
+
+    /// Find an item matching the predicate
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn find<F>(self, f: F) -> Result<Option<Item>, Self::Error>
+    where
+        F: for<'a> FnMut(&'a Item) -> bool,
+    {
+        Configured::wrap(self).find(f)
+    }
+
+    /// Count the items of the sequence.
+    ///
+    /// Use [`IgnoredAny`](serde::de::IgnoredAny) as item type to count items
+    /// without deserializing them. As nothing else tells the item type, it is named
+    /// on the trait.
+    ///
+    /// ```
+    /// use serde::de::IgnoredAny;
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"[1, "two", [3]]"#);
+    /// let count = DeserializerExt::<IgnoredAny>::count(&mut json_deserializer).unwrap();
+    /// assert_eq!(count, 3);
+    /// ```
+    fn count(self) -> Result<usize, Self::Error> {
+        Configured::wrap(self).count::<Item>()
+    }
+
+    /// Test whether any item of the sequence matches the predicate.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    ///
+    /// ```
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str("[1, 2, 3]");
+    /// assert!(json_deserializer.any(|item: u32| item > 2).unwrap());
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str("[1, 2, 3]");
+    /// assert!(!json_deserializer.any(|item: u32| item > 3).unwrap());
+    /// ```
+    fn any<F>(self, f: F) -> Result<bool, Self::Error>
+    where
+        F: FnMut(Item) -> bool,
+    {
+        Configured::wrap(self).any(f)
+    }
+
+    /// Test whether all items of the sequence match the predicate.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn all<F>(self, f: F) -> Result<bool, Self::Error>
+    where
+        F: FnMut(Item) -> bool,
+    {
+        Configured::wrap(self).all(f)
+    }
+
+    /// Find the position (starting from 0) of the first item matching the predicate.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn position<F>(self, f: F) -> Result<Option<usize>, Self::Error>
+    where
+        F: FnMut(Item) -> bool,
+    {
+        Configured::wrap(self).position(f)
+    }
+
+    /// Return the first non-`None` result of the function applied to the items.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn find_map<B, F>(self, f: F) -> Result<Option<B>, Self::Error>
+    where
+        F: FnMut(Item) -> Option<B>,
+    {
+        Configured::wrap(self).find_map(f)
+    }
+
+    /// Return the last item of the sequence.
+    fn last(self) -> Result<Option<Item>, Self::Error> {
+        Configured::wrap(self).last()
+    }
+
+    /// Return the `n`-th item (starting from 0) of the sequence.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn nth(self, n: usize) -> Result<Option<Item>, Self::Error> {
+        Configured::wrap(self).nth(n)
+    }
+
+    /// Return the item with the minimum key. If several items are equally minimum,
+    /// the first one is returned.
+    fn min_by_key<B, F>(self, f: F) -> Result<Option<Item>, Self::Error>
+    where
+        B: Ord,
+        F: FnMut(&Item) -> B,
+    {
+        Configured::wrap(self).min_by_key(f)
+    }
+
+    /// Return the item with the maximum key. If several items are equally maximum,
+    /// the last one is returned.
+    fn max_by_key<B, F>(self, f: F) -> Result<Option<Item>, Self::Error>
+    where
+        B: Ord,
+        F: FnMut(&Item) -> B,
+    {
+        Configured::wrap(self).max_by_key(f)
+    }
+
+    /// Sum the items of the sequence.
+    ///
+    /// The item type is named on the trait, see [`count`](DeserializerExt::count).
+    fn sum<S>(self) -> Result<S, Self::Error>
+    where
+        S: Sum<Item>,
+    {
+        Configured::wrap(self).sum::<Item, S>()
+    }
+
+    /// Multiply the items of the sequence.
+    ///
+    /// The item type is named on the trait, see [`count`](DeserializerExt::count).
+    fn product<P>(self) -> Result<P, Self::Error>
+    where
+        P: Product<Item>,
+    {
+        Configured::wrap(self).product::<Item, P>()
+    }
+
+    /// Reduce the items of the sequence to a single one by repeatedly applying the function.
+    fn reduce<F>(self, f: F) -> Result<Option<Item>, Self::Error>
+    where
+        F: FnMut(Item, Item) -> Item,
+    {
+        Configured::wrap(self).reduce(f)
+    }
+
+    /// Split the items of the sequence in two collections, the first one holding the items
+    /// matching the predicate, and the second one the others.
+    fn partition<B, F>(self, f: F) -> Result<(B, B), Self::Error>
+    where
+        B: Default + Extend<Item>,
+        F: FnMut(&Item) -> bool,
+    {
+        Configured::wrap(self).partition(f)
+    }
+
+    /// Aggregate all items of the sequence using a [`deep`](crate::deep) aggregator.
+    ///
+    /// The options of this deserializer are used, rather than those of the aggregator.
+    fn aggregate<A>(self) -> Result<A::Value, Self::Error>
+    where
+        A: Aggregator<Item = Item>,
+    {
+        Configured::wrap(self).aggregate::<A>()
+    }
+
+    /// Aggregate all items of the sequence with several folding closures in a single pass.
+    ///
+    /// `fs` is a tuple of (up to six) closures, each of them receiving a reference to
+    /// the item, and `init` is the tuple of their initial accumulators.
+    ///
+    /// ```
+    /// use serde_deser_iter::top_level::DeserializerExt;
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str("[3, 1, 4, 1, 5]");
+    /// let (count, max) = json_deserializer
+    ///     .fold_tuple(
+    ///         (0, 0),
+    ///         (
+    ///             |count, _: &u32| count + 1,
+    ///             |max: u32, item: &u32| max.max(*item),
+    ///         ),
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!((count, max), (5, 5));
+    /// ```
+    fn fold_tuple<Acc, T>(self, init: Acc, fs: T) -> Result<Acc, Self::Error>
+    where
+        T: FoldTuple<Item, Acc>,
+    {
+        Configured::wrap(self).fold_tuple(init, fs)
+    }
+
+    /// Aggregate all items of the sequence with several fallible/early-returning folding
+    /// closures in a single pass.
+    ///
+    /// The result of each closure is returned. Closures which returned early do not
+    /// receive the following items, and the whole aggregation only returns early once
+    /// all of them have.
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn try_fold_tuple<Init, T>(self, init: Init, fs: T) -> Result<T::Output, Self::Error>
+    where
+        T: TryFoldTuple<Item, Init>,
+    {
+        Configured::wrap(self).try_fold_tuple(init, fs)
+    }
+}
+
+impl<'de, Item, D> DeserializerExt<'de, Item> for D
+where
+    D: Deserializer<'de>,
+    Item: Deserialize<'de>,
+{
+}
+
+/// Extension trait processing a top-level map entry by entry, the counterpart of
+/// [`DeserializerExt`] for maps.
+pub trait MapDeserializerExt<'de, K, V>: Deserializer<'de>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    /// Aggregate all entries of the map using a fallible/early-returning function.
+    ///
+    /// Entries are deserialized one at a time, as `(key, value)` pairs. The [`Options`] apply
+    /// to them as to the items of a sequence, an entry being located by its index in the map.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    ///
+    /// use serde_deser_iter::{top_level::AdaptersExt, Options};
+    ///
+    /// let json = r#"{"a": 1, "b": "x", "c": 3}"#;
+    /// let error = serde_json::Deserializer::from_str(json)
+    ///     .with_options(Options::new().error_context(true))
+    ///     .try_fold_entries(0, |acc, (_, value): (String, u32)| {
+    ///         ControlFlow::<(), _>::Continue(acc + value)
+    ///     })
+    ///     .unwrap_err();
+    /// assert!(error.to_string().starts_with("[1]: invalid type"));
+    /// ```
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn try_fold_entries<Acc, Err, F>(
+        self,
+        init: Acc,
+        f: F,
+    ) -> Result<ControlFlow<Err, Acc>, Self::Error>
+    where
+        F: FnMut(Acc, (K, V)) -> ControlFlow<Err, Acc>,
+    {
+        Configured::wrap(self).try_fold_entries(init, f)
+    }
+
+    /// Aggregate all entries of the map. If the function may fail or needs to return early
+    /// use [try_fold_entries](`MapDeserializerExt::try_fold_entries`).
+    ///
+    /// ```
+    /// use serde_deser_iter::top_level::MapDeserializerExt;
+    ///
+    /// let json = r#"{"17": {"age": 31}, "42": {"age": 12}}"#;
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(json);
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct User {
+    ///     age: u32,
+    /// }
+    ///
+    /// let oldest = json_deserializer
+    ///     .fold_entries(None, |oldest: Option<(u64, u32)>, (id, user): (u64, User)| {
+    ///         match oldest {
+    ///             Some((_, age)) if age >= user.age => oldest,
+    ///             _ => Some((id, user.age)),
+    ///         }
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(oldest, Some((17, 31)));
+    /// ```
+    fn fold_entries<Acc, F>(self, init: Acc, f: F) -> Result<Acc, Self::Error>
+    where
+        F: FnMut(Acc, (K, V)) -> Acc,
+    {
+        Configured::wrap(self).fold_entries(init, f)
+    }
+
+    /// Run a cloture with side-effects on all entries of the map.
+    fn for_each_entry<F>(self, f: F) -> Result<(), Self::Error>
+    where
+        F: FnMut((K, V)),
+    {
+        Configured::wrap(self).for_each_entry(f)
+    }
+
+    /// Find an entry of the map matching the predicate
+    ///
+    /// **Caution:** The early return [caveat](../index.html#early-returns) applies.
+    fn find_entry<F>(self, f: F) -> Result<Option<(K, V)>, Self::Error>
+    where
+        F: for<'a> FnMut(&'a (K, V)) -> bool,
+    {
+        Configured::wrap(self).find_entry(f)
+    }
+}
+
+impl<'de, K, V, D> MapDeserializerExt<'de, K, V> for D
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+}
+
+/// Extension trait setting how a deserializer is processed by the methods of
+/// [`DeserializerExt`] and [`MapDeserializerExt`].
+pub trait AdaptersExt<'de>: Deserializer<'de> {
+    /// Use `options` to process the sequence.
+    ///
+    /// ```
+    /// use serde_deser_iter::{
+    ///     top_level::{AdaptersExt, DeserializerExt},
+    ///     Drain, Options,
+    /// };
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"[1, 2, "three"]"#);
+    /// let found = json_deserializer.find(|item: &u32| *item == 2);
+    /// assert_eq!(found.unwrap(), Some(2));
+    ///
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(r#"[1, 2, "three"]"#);
+    /// let found = json_deserializer
+    ///     .with_options(Options::new().drain(Drain::Validate))
+    ///     .find(|item: &u32| *item == 2);
+    /// assert!(found.is_err());
+    /// ```
+    fn with_options(self, options: Options) -> Configured<Self> {
+        Configured::new(self, options, ())
+    }
+
+    /// Descend along `path` before reaching the sequence to work on.
+    ///
+    /// Each step of the path is either a map key (given as `&str`) or a position in
+    /// a sequence (given as `usize`), see [`PathSegment`]. Values which are not on
+    /// the path are skipped. All the methods of this trait can then be called on the
+    /// returned deserializer, which keeps the options already set.
+    ///
+    /// ```
+    /// use serde_deser_iter::top_level::{AdaptersExt, DeserializerExt};
+    ///
+    /// let json = r#"{"api_version": "x.y.z", "result": [{"id": 0}, {"id": 1}, {"id": 2}]}"#;
+    /// let mut json_deserializer = serde_json::Deserializer::from_str(json);
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Entry {
+    ///     id: u32,
+    /// }
+    ///
+    /// let mut total = 0;
+    /// json_deserializer
+    ///     .at_path(&["result"])
+    ///     .for_each(|entry: Entry| total += entry.id)
+    ///     .unwrap();
+    /// assert_eq!(total, 3);
+    /// ```
+    fn at_path<'p, P>(self, path: &'p [P]) -> AtPath<'p, P, Self>
+    where
+        P: AsPathSegment,
+    {
+        AtPath::new(self, path)
+    }
+}
+
+impl<'de, D> AdaptersExt<'de> for D where D: Deserializer<'de> {}
@@ -0,0 +1,263 @@
+use serde::{
+    de::{DeserializeSeed, Visitor},
+    Deserializer,
+};
+
+use crate::deep::{AtField, AtIndex};
+
+/// A step of the path leading to a nested sequence, see [`AdaptersExt::at_path`](super::AdaptersExt::at_path).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment<'a> {
+    /// Descend in the value associated to this key of a map.
+    Key(&'a str),
+    /// Descend in the element at this position (starting from 0) of a sequence.
+    Index(usize),
+}
+
+/// Types which can be used as steps in a path.
+pub trait AsPathSegment {
+    /// Obtain the corresponding path segment.
+    fn as_path_segment(&self) -> PathSegment<'_>;
+}
+
+impl<'a> AsPathSegment for PathSegment<'a> {
+    fn as_path_segment(&self) -> PathSegment<'_> {
+        *self
+    }
+}
+
+impl AsPathSegment for str {
+    fn as_path_segment(&self) -> PathSegment<'_> {
+        PathSegment::Key(self)
+    }
+}
+
+impl AsPathSegment for usize {
+    fn as_path_segment(&self) -> PathSegment<'_> {
+        PathSegment::Index(*self)
+    }
+}
+
+impl<T: AsPathSegment + ?Sized> AsPathSegment for &T {
+    fn as_path_segment(&self) -> PathSegment<'_> {
+        (**self).as_path_segment()
+    }
+}
+
+/// A deserializer descending along a path before deserializing the value found there.
+///
+/// It is obtained through [`AdaptersExt::at_path`](super::AdaptersExt::at_path).
+/// All values which are not on the path are skipped using [`serde::de::IgnoredAny`].
+pub struct AtPath<'p, P, D> {
+    path: &'p [P],
+    deserializer: D,
+}
+
+impl<'p, P, D> AtPath<'p, P, D> {
+    pub(super) fn new(deserializer: D, path: &'p [P]) -> Self {
+        Self { path, deserializer }
+    }
+}
+
+/// Seed navigating along the remaining path before handing the value to the inner seed.
+struct Navigate<'p, P, S> {
+    path: &'p [P],
+    seed: S,
+}
+
+impl<'de, 'p, P, S> DeserializeSeed<'de> for Navigate<'p, P, S>
+where
+    P: AsPathSegment,
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Some((segment, rest)) = self.path.split_first() else {
+            return self.seed.deserialize(deserializer);
+        };
+        let next = Navigate {
+            path: rest,
+            seed: self.seed,
+        };
+        match segment.as_path_segment() {
+            PathSegment::Key(key) => AtField::new(key, next).deserialize(deserializer),
+            PathSegment::Index(index) => AtIndex::new(index, next).deserialize(deserializer),
+        }
+    }
+}
+
+/// The [`Deserializer`] method called once the end of the path is reached.
+enum Method {
+    Any,
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    Char,
+    Str,
+    String,
+    Bytes,
+    ByteBuf,
+    Option,
+    Unit,
+    UnitStruct(&'static str),
+    NewtypeStruct(&'static str),
+    Seq,
+    Tuple(usize),
+    TupleStruct(&'static str, usize),
+    Map,
+    Struct(&'static str, &'static [&'static str]),
+    Enum(&'static str, &'static [&'static str]),
+    Identifier,
+    IgnoredAny,
+}
+
+/// Seed calling [`Method`] with the visitor.
+struct Call<V> {
+    method: Method,
+    visitor: V,
+}
+
+impl<'de, V> DeserializeSeed<'de> for Call<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let visitor = self.visitor;
+        match self.method {
+            Method::Any => deserializer.deserialize_any(visitor),
+            Method::Bool => deserializer.deserialize_bool(visitor),
+            Method::I8 => deserializer.deserialize_i8(visitor),
+            Method::I16 => deserializer.deserialize_i16(visitor),
+            Method::I32 => deserializer.deserialize_i32(visitor),
+            Method::I64 => deserializer.deserialize_i64(visitor),
+            Method::I128 => deserializer.deserialize_i128(visitor),
+            Method::U8 => deserializer.deserialize_u8(visitor),
+            Method::U16 => deserializer.deserialize_u16(visitor),
+            Method::U32 => deserializer.deserialize_u32(visitor),
+            Method::U64 => deserializer.deserialize_u64(visitor),
+            Method::U128 => deserializer.deserialize_u128(visitor),
+            Method::F32 => deserializer.deserialize_f32(visitor),
+            Method::F64 => deserializer.deserialize_f64(visitor),
+            Method::Char => deserializer.deserialize_char(visitor),
+            Method::Str => deserializer.deserialize_str(visitor),
+            Method::String => deserializer.deserialize_string(visitor),
+            Method::Bytes => deserializer.deserialize_bytes(visitor),
+            Method::ByteBuf => deserializer.deserialize_byte_buf(visitor),
+            Method::Option => deserializer.deserialize_option(visitor),
+            Method::Unit => deserializer.deserialize_unit(visitor),
+            Method::UnitStruct(name) => deserializer.deserialize_unit_struct(name, visitor),
+            Method::NewtypeStruct(name) => deserializer.deserialize_newtype_struct(name, visitor),
+            Method::Seq => deserializer.deserialize_seq(visitor),
+            Method::Tuple(len) => deserializer.deserialize_tuple(len, visitor),
+            Method::TupleStruct(name, len) => {
+                deserializer.deserialize_tuple_struct(name, len, visitor)
+            }
+            Method::Map => deserializer.deserialize_map(visitor),
+            Method::Struct(name, fields) => deserializer.deserialize_struct(name, fields, visitor),
+            Method::Enum(name, variants) => deserializer.deserialize_enum(name, variants, visitor),
+            Method::Identifier => deserializer.deserialize_identifier(visitor),
+            Method::IgnoredAny => deserializer.deserialize_ignored_any(visitor),
+        }
+    }
+}
+
+impl<'de, 'p, P, D> AtPath<'p, P, D>
+where
+    P: AsPathSegment,
+    D: Deserializer<'de>,
+{
+    fn navigate<V>(self, method: Method, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let seed = Navigate {
+            path: self.path,
+            seed: Call { method, visitor },
+        };
+        seed.deserialize(self.deserializer)
+    }
+}
+
+macro_rules! navigate_to {
+    ($($method:ident($($arg:ident: $ty:ty),*) => $variant:expr;)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.navigate($variant, visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'p, P, D> Deserializer<'de> for AtPath<'p, P, D>
+where
+    P: AsPathSegment,
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    navigate_to! {
+        deserialize_any() => Method::Any;
+        deserialize_bool() => Method::Bool;
+        deserialize_i8() => Method::I8;
+        deserialize_i16() => Method::I16;
+        deserialize_i32() => Method::I32;
+        deserialize_i64() => Method::I64;
+        deserialize_i128() => Method::I128;
+        deserialize_u8() => Method::U8;
+        deserialize_u16() => Method::U16;
+        deserialize_u32() => Method::U32;
+        deserialize_u64() => Method::U64;
+        deserialize_u128() => Method::U128;
+        deserialize_f32() => Method::F32;
+        deserialize_f64() => Method::F64;
+        deserialize_char() => Method::Char;
+        deserialize_str() => Method::Str;
+        deserialize_string() => Method::String;
+        deserialize_bytes() => Method::Bytes;
+        deserialize_byte_buf() => Method::ByteBuf;
+        deserialize_option() => Method::Option;
+        deserialize_unit() => Method::Unit;
+        deserialize_unit_struct(name: &'static str) => Method::UnitStruct(name);
+        deserialize_newtype_struct(name: &'static str) => Method::NewtypeStruct(name);
+        deserialize_seq() => Method::Seq;
+        deserialize_tuple(len: usize) => Method::Tuple(len);
+        deserialize_tuple_struct(name: &'static str, len: usize) => Method::TupleStruct(name, len);
+        deserialize_map() => Method::Map;
+        deserialize_struct(
+            name: &'static str,
+            fields: &'static [&'static str]
+        ) => Method::Struct(name, fields);
+        deserialize_enum(
+            name: &'static str,
+            variants: &'static [&'static str]
+        ) => Method::Enum(name, variants);
+        deserialize_identifier() => Method::Identifier;
+        deserialize_ignored_any() => Method::IgnoredAny;
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
+    }
+}
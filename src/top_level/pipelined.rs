@@ -0,0 +1,111 @@
+use alloc::vec::Vec;
+use core::{
+    fmt, mem,
+    ops::ControlFlow,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use std::{panic, sync::mpsc, thread};
+
+use serde::{Deserialize, Deserializer};
+
+use super::{try_fold_seq, Configured};
+use crate::{elements::SkippedSink, Drain};
+
+/// Number of items handed at once to the thread running the closure.
+const BATCH_SIZE: usize = 64;
+
+/// Number of batches waiting for the thread running the closure.
+const BUFFERED_BATCHES: usize = 4;
+
+/// Why the items stopped being handed to the thread running the closure.
+enum Stop {
+    /// The closure returned early.
+    Break,
+    /// The closure panicked.
+    Panic,
+}
+
+/// Fail right away when the closure panicked, without reading the rest of the input.
+fn panic_error<'s>(state: ControlFlow<&'s Stop, &'s ()>) -> Option<&'s dyn fmt::Display> {
+    match state {
+        ControlFlow::Break(Stop::Panic) => Some(&"the closure panicked"),
+        _ => None,
+    }
+}
+
+/// Implementation of [`DeserializerExt::pipelined_try_fold`].
+pub(super) fn pipelined_try_fold<'de, D, S, Item, Acc, Err, F>(
+    deserializer: Configured<D, S>,
+    init: Acc,
+    mut f: F,
+) -> Result<ControlFlow<Err, Acc>, D::Error>
+where
+    D: Deserializer<'de>,
+    S: SkippedSink,
+    Item: Deserialize<'de> + Send,
+    Acc: Send,
+    Err: Send,
+    F: FnMut(Acc, Item) -> ControlFlow<Err, Acc> + Send,
+{
+    let (deserializer, options, sink) = deserializer.into_parts();
+    let (sender, receiver) = mpsc::sync_channel::<Vec<Item>>(BUFFERED_BATCHES);
+    // Set before the receiver is dropped when the closure returns early, so that it is
+    // told apart from a panic.
+    let broke = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let broke = &broke;
+        let worker = scope.spawn(move || {
+            let mut acc = init;
+            for batch in receiver {
+                for item in batch {
+                    match f(acc, item) {
+                        ControlFlow::Continue(new_acc) => acc = new_acc,
+                        ControlFlow::Break(clot_break) => {
+                            broke.store(true, Ordering::Release);
+                            return ControlFlow::Break(clot_break);
+                        }
+                    }
+                }
+            }
+            ControlFlow::Continue(acc)
+        });
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        // The items are read until the closure has returned early, and then drained.
+        let configured = Configured::new(deserializer, options, sink);
+        let res = try_fold_seq(
+            configured,
+            (),
+            |(), item| {
+                batch.push(item);
+                if batch.len() < BATCH_SIZE {
+                    return ControlFlow::Continue(());
+                }
+                let full = mem::replace(&mut batch, Vec::with_capacity(BATCH_SIZE));
+                match sender.send(full) {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(_) if broke.load(Ordering::Acquire) => ControlFlow::Break(Stop::Break),
+                    Err(_) => ControlFlow::Break(Stop::Panic),
+                }
+            },
+            panic_error,
+        );
+        // The items read before an error are processed as well, as the closure may return
+        // early on one of them. It may also have returned early in the meantime.
+        if !batch.is_empty() {
+            let _ = sender.send(batch);
+        }
+        drop(sender);
+        let state = worker
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload));
+        match (res, state) {
+            // `Drain::Abort` does not read the input after an early return, so the errors
+            // met while reading ahead of it are not reported.
+            (Err(_), ControlFlow::Break(clot_break)) if options.drain == Drain::Abort => {
+                Ok(ControlFlow::Break(clot_break))
+            }
+            (Err(e), _) => Err(e),
+            (Ok(_), state) => Ok(state),
+        }
+    })
+}
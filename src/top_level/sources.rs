@@ -0,0 +1,383 @@
+use alloc::vec::Vec;
+use core::{fmt, num::NonZeroUsize, ops::ControlFlow};
+use std::{
+    error, panic,
+    sync::{Mutex, PoisonError},
+    thread,
+};
+
+use serde::{Deserialize, Deserializer};
+
+use super::{try_fold_seq, AdaptersExt};
+use crate::{deep::MergeableAggregator, Merge, Options};
+
+/// Several sources holding a top-level sequence each, such as the shards of an export,
+/// processed one after the other or in parallel, and whose results are merged.
+///
+/// Each source is given as a factory building its format deserializer. A source whose
+/// deserialization fails is reported in the [`Merged`] result along with its index,
+/// without stopping the processing of the others.
+///
+/// ```
+/// use std::{collections::HashSet, io::BufReader};
+///
+/// use serde_deser_iter::top_level::Sources;
+///
+/// #[derive(serde::Deserialize)]
+/// struct DataEntry {
+///     subscribed_to: Vec<String>,
+/// }
+///
+/// let shards: [&[u8]; 3] = [
+///     br#"[{"subscribed_to": ["rust", "knitting"]}, {"subscribed_to": ["sticks"]}]"#,
+///     br#"[{"subscribed_to": ["rust", "hiking"]}, {"subscribed_to": 42}]"#,
+///     br#"[{"subscribed_to": ["rust", "rugby"]}]"#,
+/// ];
+/// let readers = shards.into_iter().map(BufReader::new);
+/// let channels = Sources::new(readers.map(|reader| {
+///     move || serde_json::Deserializer::from_reader(reader)
+/// }))
+/// .fold(HashSet::new(), |mut channels, entry: DataEntry| {
+///     channels.extend(entry.subscribed_to);
+///     channels
+/// });
+/// assert_eq!(channels.value().len(), 4);
+/// assert_eq!(channels.errors().len(), 1);
+/// assert_eq!(channels.errors()[0].index(), 1);
+/// ```
+pub struct Sources<I> {
+    sources: I,
+    options: Options,
+}
+
+impl<I> Sources<I> {
+    /// Process the sequences of the `sources`, each of them being a factory building
+    /// a format deserializer.
+    pub fn new(sources: I) -> Self {
+        Self {
+            sources,
+            options: Options::new(),
+        }
+    }
+
+    /// Use `options` to process the sequences of the top-level folds.
+    ///
+    /// The [`deep`](crate::deep) aggregators use their own options.
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Aggregate the items of each source with `f`, starting from a clone of `init`,
+    /// see [`DeserializerExt::fold`](super::DeserializerExt::fold), and merge the accumulators in the order of the sources.
+    ///
+    /// As it is used once per source, `init` should be neutral for [`Merge`], such as `0`
+    /// or an empty collection: otherwise, it is merged as many times as there are sources.
+    pub fn fold<'de, Item, Acc, D, E, S, F>(self, init: Acc, mut f: F) -> Merged<Acc, E>
+    where
+        I: IntoIterator<Item = S>,
+        S: FnOnce() -> D,
+        for<'a> &'a mut D: Deserializer<'de, Error = E>,
+        Item: Deserialize<'de>,
+        Acc: Clone + Merge,
+        F: FnMut(Acc, Item) -> Acc,
+    {
+        let options = self.options;
+        let results = self.sources.into_iter().enumerate().map(|(index, source)| {
+            let mut deserializer = source();
+            let res = (&mut deserializer)
+                .with_options(options)
+                .fold(init.clone(), &mut f);
+            (index, res)
+        });
+        merge_results(results, Merge::merge, || init.clone())
+    }
+
+    /// Same as [`fold`](Sources::fold), processing the sources on a pool of worker threads.
+    pub fn par_fold<'de, Item, Acc, D, E, S, F>(self, init: Acc, f: F) -> Merged<Acc, E>
+    where
+        I: IntoIterator<Item = S>,
+        I::IntoIter: Send,
+        S: FnOnce() -> D,
+        for<'a> &'a mut D: Deserializer<'de, Error = E>,
+        Item: Deserialize<'de>,
+        Acc: Clone + Merge + Send + Sync,
+        E: Send,
+        F: Fn(Acc, Item) -> Acc + Sync,
+    {
+        let options = self.options;
+        let results = par_process(self.sources.into_iter(), |source| {
+            let mut deserializer = source();
+            (&mut deserializer)
+                .with_options(options)
+                .fold(init.clone(), &f)
+        });
+        merge_results(results, Merge::merge, || init.clone())
+    }
+
+    /// Aggregate the items of each source with `A`, merging the accumulators in the order
+    /// of the sources before the final value is obtained.
+    ///
+    /// ```
+    /// use serde_deser_iter::{deep::Sum, top_level::Sources};
+    ///
+    /// let shards = ["[1, 2]", "[3, 4]", "[5, 6]"];
+    /// let sum = Sources::new(shards.map(|shard| move || serde_json::Deserializer::from_str(shard)))
+    ///     .par_aggregate::<Sum<u32>, _, _, _>();
+    /// assert_eq!(sum.into_result().unwrap(), 21);
+    /// ```
+    ///
+    /// A source whose accumulator has an [`error`](crate::deep::Aggregator::error) fails, as
+    /// it would with [`StreamSeqDeser`](crate::deep::StreamSeqDeser):
+    ///
+    /// ```
+    /// use core::{convert::Infallible, fmt, ops::ControlFlow};
+    ///
+    /// use serde_deser_iter::{
+    ///     deep::{Aggregator, MergeableAggregator},
+    ///     top_level::Sources,
+    /// };
+    ///
+    /// /// Sum of the items, which must not exceed 100 in a source.
+    /// struct Budget;
+    ///
+    /// impl Aggregator for Budget {
+    ///     type Acc = u32;
+    ///     type Item = u32;
+    ///     type Break = Infallible;
+    ///     type Value = u32;
+    ///
+    ///     fn init() -> u32 {
+    ///         0
+    ///     }
+    ///
+    ///     fn try_fold(acc: u32, item: u32) -> ControlFlow<Infallible, u32> {
+    ///         ControlFlow::Continue(acc + item)
+    ///     }
+    ///
+    ///     fn finalize(x: ControlFlow<Infallible, u32>) -> u32 {
+    ///         match x {
+    ///             ControlFlow::Continue(acc) => acc,
+    ///             ControlFlow::Break(never) => match never {},
+    ///         }
+    ///     }
+    ///
+    ///     fn error<'s>(state: ControlFlow<&'s Infallible, &'s u32>) -> Option<&'s dyn fmt::Display> {
+    ///         match state {
+    ///             ControlFlow::Continue(acc) if *acc > 100 => Some(&"over budget"),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl MergeableAggregator for Budget {
+    ///     fn merge(a: u32, b: u32) -> u32 {
+    ///         a + b
+    ///     }
+    /// }
+    ///
+    /// let shards = ["[40, 50]", "[60, 70]", "[5]"];
+    /// let total = Sources::new(shards.map(|shard| move || serde_json::Deserializer::from_str(shard)))
+    ///     .aggregate::<Budget, _, _, _>();
+    /// assert_eq!(*total.value(), 95);
+    /// assert_eq!(total.errors().len(), 1);
+    /// assert_eq!(total.errors()[0].index(), 1);
+    /// assert!(total.errors()[0].error().to_string().starts_with("over budget"));
+    /// ```
+    pub fn aggregate<'de, A, D, E, S>(self) -> Merged<A::Value, E>
+    where
+        I: IntoIterator<Item = S>,
+        S: FnOnce() -> D,
+        for<'a> &'a mut D: Deserializer<'de, Error = E>,
+        A: MergeableAggregator,
+        A::Item: Deserialize<'de>,
+    {
+        let results = self
+            .sources
+            .into_iter()
+            .enumerate()
+            .map(|(index, source)| (index, aggregate_one::<A, _, _, _>(source)));
+        merge_results(results, A::merge, A::init).map(|acc| A::finalize(ControlFlow::Continue(acc)))
+    }
+
+    /// Same as [`aggregate`](Sources::aggregate), processing the sources on a pool of
+    /// worker threads.
+    pub fn par_aggregate<'de, A, D, E, S>(self) -> Merged<A::Value, E>
+    where
+        I: IntoIterator<Item = S>,
+        I::IntoIter: Send,
+        S: FnOnce() -> D,
+        for<'a> &'a mut D: Deserializer<'de, Error = E>,
+        A: MergeableAggregator,
+        A::Item: Deserialize<'de>,
+        A::Acc: Send,
+        E: Send,
+    {
+        let results = par_process(self.sources.into_iter(), aggregate_one::<A, _, _, _>);
+        merge_results(results, A::merge, A::init).map(|acc| A::finalize(ControlFlow::Continue(acc)))
+    }
+}
+
+/// Aggregate the sequence of `source` with `A`, up to the accumulator.
+fn aggregate_one<'de, A, D, E, S>(source: S) -> Result<A::Acc, E>
+where
+    S: FnOnce() -> D,
+    for<'a> &'a mut D: Deserializer<'de, Error = E>,
+    A: MergeableAggregator,
+    A::Item: Deserialize<'de>,
+{
+    let mut deserializer = source();
+    let configured = (&mut deserializer).with_options(A::OPTIONS);
+    match try_fold_seq(configured, A::init(), A::try_fold, A::error)? {
+        ControlFlow::Continue(acc) => Ok(acc),
+        ControlFlow::Break(never) => match never {},
+    }
+}
+
+/// Run `process` on all `sources` on one worker per available core, keeping the results
+/// in the order of the sources.
+fn par_process<S, T, E, P>(
+    sources: impl Iterator<Item = S> + Send,
+    process: P,
+) -> Vec<(usize, Result<T, E>)>
+where
+    T: Send,
+    E: Send,
+    P: Fn(S) -> Result<T, E> + Sync,
+{
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let sources = Mutex::new(sources.enumerate());
+    let mut results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        // The lock is released before processing the source.
+                        let next = sources
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .next();
+                        let Some((index, source)) = next else {
+                            return results;
+                        };
+                        results.push((index, process(source)));
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .collect()
+    });
+    results.sort_unstable_by_key(|(index, _)| *index);
+    results
+}
+
+/// Merge the successful `results` in the order of the sources, starting from `init` if
+/// there are none.
+fn merge_results<T, E>(
+    results: impl IntoIterator<Item = (usize, Result<T, E>)>,
+    mut merge: impl FnMut(T, T) -> T,
+    init: impl FnOnce() -> T,
+) -> Merged<T, E> {
+    let mut value = None;
+    let mut errors = Vec::new();
+    for (index, res) in results {
+        match res {
+            Ok(v) => {
+                value = Some(match value {
+                    Some(acc) => merge(acc, v),
+                    None => v,
+                })
+            }
+            Err(error) => errors.push(SourceError { index, error }),
+        }
+    }
+    Merged {
+        value: value.unwrap_or_else(init),
+        errors,
+    }
+}
+
+/// The merged result of several [`Sources`], along with the errors of the sources which
+/// could not be processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merged<T, E> {
+    value: T,
+    errors: Vec<SourceError<E>>,
+}
+
+impl<T, E> Merged<T, E> {
+    /// The merged value of the sources processed successfully.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The errors of the sources which could not be processed, in the order of the sources.
+    pub fn errors(&self) -> &[SourceError<E>] {
+        &self.errors
+    }
+
+    /// Take ownership of the merged value and of the errors.
+    pub fn into_parts(self) -> (T, Vec<SourceError<E>>) {
+        (self.value, self.errors)
+    }
+
+    /// The merged value if all sources were processed successfully, and the first error
+    /// otherwise.
+    pub fn into_result(self) -> Result<T, SourceError<E>> {
+        match self.errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(self.value),
+        }
+    }
+
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Merged<U, E> {
+        Merged {
+            value: f(self.value),
+            errors: self.errors,
+        }
+    }
+}
+
+/// The error of one of several [`Sources`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceError<E> {
+    index: usize,
+    error: E,
+}
+
+impl<E> SourceError<E> {
+    /// Zero-based index of the source.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The deserialization error of the source.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Take ownership of the deserialization error.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for SourceError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "source {}: {}", self.index, self.error)
+    }
+}
+
+impl<E: error::Error + 'static> error::Error for SourceError<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,191 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{
+    panic,
+    sync::Arc,
+    task::Wake,
+    thread::{self, JoinHandle, Thread},
+};
+
+use futures_channel::mpsc::{self, Receiver, Sender};
+use futures_core::{stream::FusedStream, Stream};
+use serde::{de::DeserializeOwned, Deserializer};
+
+use super::{into_iter::work, DEFAULT_CAPACITY};
+use crate::Options;
+
+/// Stream the items of a sequence, deserialized on a background thread.
+///
+/// This is the asynchronous counterpart of [`into_iter`](super::into_iter): the format
+/// deserializer is built on a dedicated thread by `factory`, so that the blocking
+/// deserialization does not hold the executor, and the items are sent to the returned
+/// [`Stream`] through a bounded channel. The worker waits for the stream to be polled
+/// when [`DEFAULT_CAPACITY`] items are pending.
+///
+/// ```
+/// use futures::{executor::block_on, StreamExt};
+/// use serde_deser_iter::top_level::into_stream;
+///
+/// let stream = into_stream::<u32, _, _, _>(|| serde_json::Deserializer::from_str("[1, 2, 3]"));
+/// let items: Vec<_> = block_on(stream.map(Result::unwrap).collect());
+/// assert_eq!(items, [1, 2, 3]);
+/// ```
+pub fn into_stream<Item, D, E, F>(factory: F) -> IntoStream<Item, E>
+where
+    Item: DeserializeOwned + Send + 'static,
+    E: Send + 'static,
+    F: FnOnce() -> D + Send + 'static,
+    for<'a> &'a mut D: Deserializer<'static, Error = E>,
+{
+    IntoStream::spawn(Options::new(), DEFAULT_CAPACITY, factory)
+}
+
+/// An owned stream of the items of a sequence, see [`into_stream`].
+///
+/// It yields the items in order, followed by the deserialization error if any.
+///
+/// ```
+/// use futures::{executor::block_on, StreamExt};
+/// use serde_deser_iter::top_level::into_stream;
+///
+/// let stream = into_stream::<u32, _, _, _>(|| serde_json::Deserializer::from_str(r#"[1, "two", 3]"#));
+/// let items: Vec<_> = block_on(stream.collect());
+/// assert_eq!(items.len(), 2);
+/// assert_eq!(*items[0].as_ref().unwrap(), 1);
+/// assert!(items[1].is_err());
+/// ```
+///
+/// Dropping it before the end of the sequence stops the worker thread, which does not
+/// read the rest of the input. Contrary to [`IntoIter`](super::IntoIter), it does not wait
+/// for the worker to finish, not to block the executor.
+///
+/// ```
+/// use std::{
+///     io::{self, Read},
+///     sync::mpsc,
+/// };
+///
+/// use futures::{executor::block_on, StreamExt};
+/// use serde_deser_iter::top_level::into_stream;
+///
+/// /// The endless sequence `[1,1,1,...`, telling when it is dropped.
+/// struct Ones(usize, mpsc::Sender<()>);
+///
+/// impl Read for Ones {
+///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+///         for byte in buf.iter_mut() {
+///             *byte = [b'[', b'1', b','][self.0];
+///             self.0 = if self.0 == 1 { 2 } else { 1 };
+///         }
+///         Ok(buf.len())
+///     }
+/// }
+///
+/// let (dropped_sender, dropped) = mpsc::channel();
+/// let mut stream = into_stream::<u32, _, _, _>(move || {
+///     serde_json::Deserializer::from_reader(Ones(0, dropped_sender))
+/// });
+/// assert_eq!(block_on(stream.next()).unwrap().unwrap(), 1);
+/// drop(stream);
+/// // The worker stops reading the input and drops it.
+/// assert!(dropped.recv().is_err());
+/// ```
+///
+/// A panic of the worker thread, for instance in `factory`, is forwarded to the consumer
+/// when the stream reaches its end.
+///
+/// ```
+/// use std::panic::{self, AssertUnwindSafe};
+///
+/// use futures::{executor::block_on, StreamExt};
+/// use serde_deser_iter::top_level::into_stream;
+/// use serde_json::{de::StrRead, Deserializer};
+///
+/// let mut stream = into_stream::<u32, _, _, _>(|| -> Deserializer<StrRead<'static>> {
+///     panic!("no input")
+/// });
+/// let payload = panic::catch_unwind(AssertUnwindSafe(|| block_on(stream.next()))).unwrap_err();
+/// assert_eq!(payload.downcast_ref::<&str>(), Some(&"no input"));
+/// ```
+pub struct IntoStream<Item, E> {
+    receiver: Receiver<Result<Item, E>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<Item, E> IntoStream<Item, E>
+where
+    Item: DeserializeOwned + Send + 'static,
+    E: Send + 'static,
+{
+    /// Same as [`into_stream`], processing the sequence with `options` and buffering up to
+    /// `capacity` items ahead of the consumer.
+    ///
+    /// The [`Drain`](crate::Drain) strategy of the options is ignored: the worker always
+    /// stops reading the input when the stream is dropped.
+    pub fn spawn<D, F>(options: Options, capacity: usize, factory: F) -> Self
+    where
+        F: FnOnce() -> D + Send + 'static,
+        for<'a> &'a mut D: Deserializer<'static, Error = E>,
+    {
+        // The channel of `futures_channel` has one more slot per sender.
+        let (mut sender, receiver) = mpsc::channel(capacity.saturating_sub(1));
+        let worker = thread::spawn(move || {
+            let waker = Arc::new(Unpark(thread::current())).into();
+            let mut cx = Context::from_waker(&waker);
+            work(options, factory, |item| {
+                send_blocking(&mut sender, &mut cx, item)
+            });
+        });
+        Self {
+            receiver,
+            worker: Some(worker),
+        }
+    }
+}
+
+/// Waker unparking the worker thread.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Send `item` from the worker thread, parking it while the channel is full.
+///
+/// `cx` must unpark the worker thread when woken. Returns `false` if the stream has been dropped.
+fn send_blocking<T>(sender: &mut Sender<T>, cx: &mut Context<'_>, item: T) -> bool {
+    loop {
+        match sender.poll_ready(cx) {
+            Poll::Ready(Ok(())) => return sender.start_send(item).is_ok(),
+            Poll::Ready(Err(_)) => return false,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+impl<Item, E> Stream for IntoStream<Item, E> {
+    type Item = Result<Item, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let res = Pin::new(&mut self.receiver).poll_next(cx);
+        if let Poll::Ready(None) = res {
+            // The worker has dropped its sender and is about to finish.
+            if let Some(worker) = self.worker.take() {
+                if let Err(payload) = worker.join() {
+                    panic::resume_unwind(payload);
+                }
+            }
+        }
+        res
+    }
+}
+
+impl<Item, E> FusedStream for IntoStream<Item, E> {
+    fn is_terminated(&self) -> bool {
+        self.receiver.is_terminated()
+    }
+}
@@ -0,0 +1,82 @@
+use core::ops::ControlFlow;
+
+/// Tuples of folding closures, run simultaneously on the same items.
+///
+/// See [`DeserializerExt::fold_tuple`](super::DeserializerExt::fold_tuple).
+pub trait FoldTuple<Item, Acc> {
+    #[doc(hidden)]
+    fn step(&mut self, acc: Acc, item: &Item) -> Acc;
+}
+
+/// Tuples of fallible/early-returning folding closures, run simultaneously on the same items.
+///
+/// See [`DeserializerExt::try_fold_tuple`](super::DeserializerExt::try_fold_tuple).
+pub trait TryFoldTuple<Item, Init> {
+    /// The tuple of the results of each closure.
+    type Output;
+
+    #[doc(hidden)]
+    fn start(init: Init) -> Self::Output;
+
+    /// Returns early once all closures have.
+    #[doc(hidden)]
+    fn step(&mut self, state: Self::Output, item: &Item)
+        -> ControlFlow<Self::Output, Self::Output>;
+}
+
+macro_rules! tuple_folders {
+    ($($F:ident $Acc:ident $Break:ident $idx:tt),+) => {
+        impl<Item, $($F, $Acc,)+> FoldTuple<Item, ($($Acc,)+)> for ($($F,)+)
+        where
+            $($F: FnMut($Acc, &Item) -> $Acc,)+
+        {
+            fn step(&mut self, acc: ($($Acc,)+), item: &Item) -> ($($Acc,)+) {
+                ($((self.$idx)(acc.$idx, item),)+)
+            }
+        }
+
+        impl<Item, $($F, $Acc, $Break,)+> TryFoldTuple<Item, ($($Acc,)+)> for ($($F,)+)
+        where
+            $($F: FnMut($Acc, &Item) -> ControlFlow<$Break, $Acc>,)+
+        {
+            type Output = ($(ControlFlow<$Break, $Acc>,)+);
+
+            fn start(init: ($($Acc,)+)) -> Self::Output {
+                ($(ControlFlow::Continue(init.$idx),)+)
+            }
+
+            fn step(&mut self, state: Self::Output, item: &Item) -> ControlFlow<Self::Output, Self::Output> {
+                let state = ($(
+                    match state.$idx {
+                        ControlFlow::Continue(acc) => (self.$idx)(acc, item),
+                        ControlFlow::Break(clot_break) => ControlFlow::Break(clot_break),
+                    },
+                )+);
+                if $(state.$idx.is_break())&&+ {
+                    ControlFlow::Break(state)
+                } else {
+                    ControlFlow::Continue(state)
+                }
+            }
+        }
+    };
+}
+
+tuple_folders!(F0 Acc0 Break0 0, F1 Acc1 Break1 1);
+tuple_folders!(F0 Acc0 Break0 0, F1 Acc1 Break1 1, F2 Acc2 Break2 2);
+tuple_folders!(F0 Acc0 Break0 0, F1 Acc1 Break1 1, F2 Acc2 Break2 2, F3 Acc3 Break3 3);
+tuple_folders!(
+    F0 Acc0 Break0 0,
+    F1 Acc1 Break1 1,
+    F2 Acc2 Break2 2,
+    F3 Acc3 Break3 3,
+    F4 Acc4 Break4 4
+);
+tuple_folders!(
+    F0 Acc0 Break0 0,
+    F1 Acc1 Break1 1,
+    F2 Acc2 Break2 2,
+    F3 Acc3 Break3 3,
+    F4 Acc4 Break4 4,
+    F5 Acc5 Break5 5
+);